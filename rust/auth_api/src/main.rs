@@ -77,6 +77,7 @@ use axum::http::HeaderMap;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use ethers::providers::{Provider, Http};
 use ethers::contract::abigen;
+use press_metrics::RouterExt;
 
 abigen!(CouncilRegistry, r#"[function isCouncil(address) view returns (bool)]"#);
 abigen!(BondVault, r#"[function bonded(address,bytes32) view returns (uint256)]"#);
@@ -149,6 +150,7 @@ async fn main() {
         .route("/nonce", get(get_nonce))
         .route("/verify", post(verify))
         .route("/me", get(me))
+        .with_metrics("auth_api")
         .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
         .with_state(state);
 
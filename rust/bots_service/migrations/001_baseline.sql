@@ -0,0 +1,44 @@
+-- Bots schema as of the switch to versioned migrations; IF NOT EXISTS so existing bots.db files
+-- are adopted as version 1.
+
+CREATE TABLE IF NOT EXISTS outlet_channels (
+    outlet_id TEXT PRIMARY KEY,
+    outlet_name TEXT,
+    official_domain TEXT,
+    discord_guild_id TEXT,
+    discord_channel_id TEXT,
+    telegram_chat_id TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS role_mappings (
+    outlet_id TEXT NOT NULL,
+    press_role TEXT NOT NULL,
+    discord_role_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY(outlet_id, press_role)
+);
+
+CREATE TABLE IF NOT EXISTS wallet_links (
+    discord_user_id TEXT PRIMARY KEY,
+    wallet_address TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS oauth_sessions (
+    session_id TEXT PRIMARY KEY,
+    discord_user_id TEXT NOT NULL,
+    outlet_id TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS council_grace (
+    discord_user_id TEXT PRIMARY KEY,
+    lost_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS council_members (
+    wallet_address TEXT PRIMARY KEY,
+    joined_at INTEGER NOT NULL,
+    last_active_at INTEGER NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Cached Discord council role counts (robust_role_count_cached); the table was queried but never
+-- created.
+CREATE TABLE IF NOT EXISTS council_count_cache (
+    guild_id TEXT PRIMARY KEY,
+    role_id TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    counted_at INTEGER NOT NULL
+);
@@ -15,6 +15,7 @@ use std::sync::Arc;
 
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
+use press_metrics::RouterExt;
 
 #[derive(Clone)]
 struct AppState {
@@ -26,6 +27,7 @@ struct AppState {
 
     cfg: Arc<RwLock<BotConfig>>,
     state_path: PathBuf,
+    db: sqlx::AnyPool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -175,10 +177,11 @@ async fn main() -> anyhow::Result<()> {
 
     let telemetry = Arc::new(RwLock::new(BotTelemetry::default()));
 
-    let db = sqlx::SqlitePool::connect(&format!("sqlite:{}", cfg.state_dir.join("bots.db").display())).await.expect("bots db");
-    init_db(&db).await.expect("init bots db");
+    let db = press_migrate::connect(&format!("sqlite:{}", cfg.state_dir.join("bots.db").display())).await.map_err(anyhow::Error::msg)?;
+    init_db(&db).await?;
 
     let state = AppState {
+        db,
         cfg: Arc::new(RwLock::new(cfg)),
         state_path,
         queue_path,
@@ -235,6 +238,7 @@ async fn main() -> anyhow::Result<()> {
     let app = Router::new()
         .route("/health", get(|| async { Json(Health{ok:true, service:"press_bots_service"}) }))
         .route("/api/bots/status", get(get_status))
+        .route("/api/bots/migrations", get(get_migrations))
         .route("/api/bots/config", get(get_config).post(set_config))
         .route("/api/bots/bindings", get(get_bindings).post(add_binding))
         .route("/api/bots/admin/gate", get(get_admin_gate))
@@ -268,6 +272,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/bots/admin/missions/create", post(admin_mission_create))
         .route("/api/bots/telegram/onboarding_link", post(telegram_onboarding_link))
         .route("/api/bots/telegram/subscriptions", get(tg_get_subs).post(tg_set_subs))
+        .with_metrics("bots_service")
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -810,7 +815,9 @@ async fn telegram_verify_chat(
 async fn discord_send_test(state: &AppState, channel_id: &str, message: &str) -> bool {
     if let Some(http) = state.discord_http.read().await.clone() {
         if let Ok(cid) = channel_id.parse::<u64>() {
-            return serenity::model::id::ChannelId(cid).send_message(&http, |m| m.content(message)).await.is_ok();
+            let ok = serenity::model::id::ChannelId(cid).send_message(&http, |m| m.content(message)).await.is_ok();
+            press_metrics::bot_message("discord", ok);
+            return ok;
         }
     }
     false
@@ -820,13 +827,15 @@ async fn telegram_send_test(state: &AppState, chat_id: &str, message: &str) -> b
     let cfg = state.cfg.read().await.clone();
     if let Some(tok) = cfg.telegram_bot_token {
         let url = format!("https://api.telegram.org/bot{}/sendMessage", tok);
-        return reqwest::Client::new()
+        let ok = reqwest::Client::new()
             .post(url)
             .json(&serde_json::json!({"chat_id": chat_id, "text": message}))
             .send()
             .await
             .map(|r| r.status().is_success())
-            .unwrap_or(false) };
+            .unwrap_or(false);
+        press_metrics::bot_message("telegram", ok);
+        return ok;
 
     }
     false
@@ -874,6 +883,7 @@ async fn outlet_preflight(
                 can_send = serenity::model::id::ChannelId(cid)
                     .send_message(&http, |m| m.content("PressPulse preflight: channel verified."))
                     .await.is_ok();
+                press_metrics::bot_message("discord", can_send);
             }
             // Check manage_roles by inspecting bot member perms (best-effort)
             if let (Ok(gid), Ok(bot_user)) = (dg.parse::<u64>(), http.get_current_user().await.map(|u| u.id.0)) {
@@ -1227,6 +1237,9 @@ async fn perform_role_sync_for_user_scoped(state: &AppState, discord_user_id: &s
         .and_then(|r| async { r.json::<serde_json::Value>().await.ok() }.await)
         .unwrap_or_else(|| serde_json::json!({"roles":[]}));    
     let rarr = roles.get("roles").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    // outlet-scoped roles (OUTLET_EDITOR etc.) only count in the guild of the outlet that granted them
+    let global = roles.get("global").and_then(|v| v.as_array()).cloned();
+    let scoped = roles.get("outlets").and_then(|v| v.as_object()).cloned();
 
     // determine outlets + mapped roles
     let outlets = if outlet_id_scope.is_empty() {
@@ -1242,11 +1255,15 @@ async fn perform_role_sync_for_user_scoped(state: &AppState, discord_user_id: &s
         let maps = sqlx::query("SELECT press_role, discord_role_id FROM role_mappings WHERE outlet_id=?1")
             .bind(&outlet_id).fetch_all(&state.db).await.unwrap_or_default();
 
+        let held: Vec<serde_json::Value> = match (&global, &scoped) {
+            (Some(g), Some(o)) => g.iter().chain(o.get(&outlet_id).and_then(|v| v.as_array()).into_iter().flatten()).cloned().collect(),
+            _ => rarr.clone(),
+        };
         let mut desired: Vec<u64> = vec![];
         for m in maps {
             let pr:String=m.get(0);
             let dr:String=m.get(1);
-            let has = rarr.iter().any(|x| x.as_str().unwrap_or("")==pr);
+            let has = held.iter().any(|x| x.as_str().unwrap_or("")==pr);
             if has {
                 if let Ok(rid)=dr.parse::<u64>() { desired.push(rid); }
             }
@@ -1597,7 +1614,7 @@ async fn discord_loop(state: AppState, token: String) {
                     let label = item.cta_label.clone().unwrap_or_else(|| "Open".into());
                     content.push_str(&format!("\n{}: {}", label, u));
                 }
-                let _ = ch.say(&ctx.http, content).await;
+                press_metrics::bot_message("discord", ch.say(&ctx.http, content).await.is_ok());
             }
             // persist updated queue
             let _ = write_json_vec(&state.queue_path, &q).await;
@@ -1622,7 +1639,8 @@ async fn telegram_loop(state: AppState, token: String) {
     // Feed poller: pushes new on-chain events to subscribed chats
     tokio::spawn(telegram_feed_poller(state.clone(), bot.clone()));
 
-    
+    let inline_state = state.clone();
+
 // /start onboarding + inline verification cues
 let msg_handler = Update::filter_message().endpoint(move |bot: Bot, msg: Message| {
     let state = state.clone();
@@ -1652,23 +1670,45 @@ Inline mode: type @PressPulseBot <query> to search articles/proposals.", note);
             vec![InlineKeyboardButton::url("Verify (Press Wallet)".into(), "https://bots.pressblockchain.io/dashboard#verify".parse().unwrap())],
             vec![InlineKeyboardButton::url("Status".into(), "https://status.pressblockchain.io".parse().unwrap())],
         ]);
-        let _ = bot.send_message(msg.chat.id, text).reply_markup(kb).await;
+        press_metrics::bot_message("telegram", bot.send_message(msg.chat.id, text).reply_markup(kb).await.is_ok());
         let _ = state;
         respond(())
     }
 });
 
-// Inline query: placeholder "search" responses; RR83 will connect to query-api/indexer.
-let inline_handler = Update::filter_inline_query().endpoint(|bot: Bot, q: teloxide::types::InlineQuery| async move {
-    use teloxide::types::{InlineQueryResultArticle, InputMessageContentText, InlineQueryResult};
-    let query = q.query.clone();
-    let article = InlineQueryResultArticle::new(
-        "presspulse_search_1".into(),
-        format!("Search: {}", query),
-        InputMessageContentText::new(format!("Search results for: {}\n\n{}", query, snippet))
-    ).description("On-chain search (coming online)".into());
-    bot.answer_inline_query(q.id, vec![InlineQueryResult::Article(article)]).await.ok();
-    respond(())
+// Inline query: search articles/proposals through query-api (indexer FTS).
+let inline_handler = Update::filter_inline_query().endpoint(move |bot: Bot, q: teloxide::types::InlineQuery| {
+    let state = inline_state.clone();
+    async move {
+        use teloxide::types::{InlineQueryResultArticle, InputMessageContentText, InlineQueryResult};
+        let query = q.query.clone();
+        let api = state.cfg.read().await.press_query_api.clone();
+        let url = format!("{}/api/search?q={}&limit=10", api, urlencoding::encode(&query));
+        let items = match reqwest::Client::new().get(url).send().await {
+            Ok(r) => r.json::<serde_json::Value>().await.ok()
+                .and_then(|j| j.get("items").and_then(|v| v.as_array()).cloned())
+                .unwrap_or_default(),
+            Err(_) => vec![],
+        };
+        let plain = |s: &str| s.replace("<mark>", "").replace("</mark>", "");
+        let mut results = vec![];
+        for (i, it) in items.iter().enumerate() {
+            let title = plain(it.get("title").and_then(|v| v.as_str()).unwrap_or(""));
+            let snippet = plain(it.get("snippet").and_then(|v| v.as_str()).unwrap_or(""));
+            let reference = match it.get("article_id").and_then(|v| v.as_str()) {
+                Some(a) => format!("Article {}", a),
+                None => format!("Proposal #{}", it.get("proposal_id").and_then(|v| v.as_i64()).unwrap_or(0)),
+            };
+            let article = InlineQueryResultArticle::new(
+                format!("presspulse_search_{}", i),
+                if title.is_empty() { reference.clone() } else { title.clone() },
+                InputMessageContentText::new(format!("{}\n{}\n\n{}", title, reference, snippet))
+            ).description(if snippet.is_empty() { reference } else { snippet });
+            results.push(InlineQueryResult::Article(article));
+        }
+        bot.answer_inline_query(q.id, results).await.ok();
+        respond(())
+    }
 });
 
 let handler = dptree::entry()
@@ -1689,8 +1729,11 @@ Dispatcher::builder(bot, handler)
 struct TgSubs {
     // chat_id -> list of feeds
     chats: std::collections::HashMap<String, Vec<String>>,
-    // last sent cursor per feed
+    // last sent cursor per feed (legacy: unix ts only)
     cursors: std::collections::HashMap<String, i64>,
+    // last sent "ts:id" feed cursor per chat+feed; preferred over `cursors`
+    #[serde(default)]
+    feed_cursors: std::collections::HashMap<String, String>,
 }
 
 async fn tg_read_subs(path: &PathBuf) -> TgSubs {
@@ -1741,8 +1784,11 @@ async fn telegram_feed_poller(state: AppState, bot: teloxide::Bot) {
                 // cursor key per chat+feed
                 let ckey = format!("{}:{}", chat_id, feed);
                 let cursor = subs.cursors.get(&ckey).cloned().unwrap_or(0);
-                // query API for feed events
-                let url = format!("{}/api/feed/{}?after={}", api, feed, cursor);
+                // query API for feed events; prefer the (ts,id) cursor so same-second items aren't skipped
+                let url = match subs.feed_cursors.get(&ckey) {
+                    Some(c) => format!("{}/api/feed/{}?cursor={}&limit=10", api, feed, urlencoding::encode(c)),
+                    None => format!("{}/api/feed/{}?after={}&limit=10", api, feed, cursor),
+                };
                 if let Ok(r) = reqwest::Client::new().get(url).send().await {
                     if let Ok(j) = r.json::<serde_json::Value>().await {
                         if let Some(items) = j.get("items").and_then(|v| v.as_array()) {
@@ -1761,10 +1807,13 @@ let src = it.get("source").and_then(|v| v.as_str()).unwrap_or("");
 } else {
     format!("{}\n{}", title, url)
 };
-                                let _ = bot.send_message(teloxide::types::ChatId(chat), msg).await;
+                                press_metrics::bot_message("telegram", bot.send_message(teloxide::types::ChatId(chat), msg).await.is_ok());
                                 if ts > max_ts { max_ts = ts; }
                             }
                             if max_ts > cursor { subs.cursors.insert(ckey.clone(), max_ts); }
+                            if let Some(nc) = j.get("next_cursor").and_then(|v| v.as_str()) {
+                                subs.feed_cursors.insert(ckey.clone(), nc.to_string());
+                            }
                         }
                     }
                 }
@@ -1780,7 +1829,11 @@ let src = it.get("source").and_then(|v| v.as_str()).unwrap_or("");
 async fn oracle_alert_poller(state: AppState) {
     // Persist cursor in /state/oracle_alert_cursor.json
     let cursor_path = PathBuf::from(env::var("PRESS_ORACLE_CURSOR").unwrap_or_else(|_| "/state/oracle_alert_cursor.json".into()));
-    let mut cursor: i64 = read_json_i64(&cursor_path).await.unwrap_or(0);
+    // "ts:id" feed cursor; older cursor files hold a bare unix ts, meaning "after that second"
+    let mut cursor: String = match read_json_i64(&cursor_path).await {
+        Some(ts) => format!("{}:{}", ts, i64::MAX),
+        None => read_json_string(&cursor_path).await.unwrap_or_else(|| "0:0".into()),
+    };
 
     loop {
         let cfg = state.cfg.read().await.clone();
@@ -1789,13 +1842,12 @@ async fn oracle_alert_poller(state: AppState) {
             continue;
         }
 
-        let url = format!("{}/api/feed/oracle_flags?after={}&severity={}", cfg.press_query_api, cursor, cfg.oracle_min_severity);
+        let url = format!("{}/api/feed/oracle_flags?cursor={}&severity={}&limit=20", cfg.press_query_api, urlencoding::encode(&cursor), cfg.oracle_min_severity);
         if let Ok(r) = reqwest::Client::new().get(url).send().await {
             if let Ok(j) = r.json::<serde_json::Value>().await {
                 if let Some(items) = j.get("items").and_then(|v| v.as_array()) {
-                    let mut max_ts = cursor;
                     for it in items.iter().take(20) {
-                        let ts = it.get("ts").and_then(|v| v.as_i64()).unwrap_or(cursor);
+                        let ts = it.get("ts").and_then(|v| v.as_i64()).unwrap_or(0);
                         let sev = it.get("severity").and_then(|v| v.as_i64()).unwrap_or(1);
                         let kind = it.get("kind").and_then(|v| v.as_str()).unwrap_or("oracle");
                         let aid = it.get("article_id").and_then(|v| v.as_str()).unwrap_or("");
@@ -1817,12 +1869,12 @@ async fn oracle_alert_poller(state: AppState) {
                             scope_guild_id: None,
                         });
                         let _ = write_json_vec(&state.queue_path, &q).await;
-
-                        if ts > max_ts { max_ts = ts; }
                     }
-                    if max_ts > cursor {
-                        cursor = max_ts;
-                        let _ = write_json_i64(&cursor_path, cursor).await;
+                    if let Some(nc) = j.get("next_cursor").and_then(|v| v.as_str()) {
+                        if nc != cursor {
+                            cursor = nc.to_string();
+                            let _ = write_json_string(&cursor_path, &cursor).await;
+                        }
                     }
                 }
             }
@@ -1845,6 +1897,17 @@ async fn write_json_i64(path: &PathBuf, v: i64) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn read_json_string(path: &PathBuf) -> Option<String> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    serde_json::from_slice::<String>(&bytes).ok()
+}
+
+async fn write_json_string(path: &PathBuf, v: &str) -> anyhow::Result<()> {
+    if let Some(p) = path.parent() { tokio::fs::create_dir_all(p).await.ok(); }
+    tokio::fs::write(path, serde_json::to_vec_pretty(&v)?).await?;
+    Ok(())
+}
+
 
 #[derive(Clone, Serialize, Deserialize, Default)]
 struct BotTelemetry {
@@ -1987,53 +2050,22 @@ async fn features_watcher_loop(state: AppState) {
 }
 
 
-async fn init_db(db: &sqlx::SqlitePool) -> anyhow::Result<()> {
-    sqlx::query(r#"
-        CREATE TABLE IF NOT EXISTS outlet_channels (
-            outlet_id TEXT PRIMARY KEY,
-            outlet_name TEXT,
-            official_domain TEXT,
-            discord_guild_id TEXT,
-            discord_channel_id TEXT,
-            telegram_chat_id TEXT,
-            created_at INTEGER NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS role_mappings (
-            outlet_id TEXT NOT NULL,
-            press_role TEXT NOT NULL,
-            discord_role_id TEXT NOT NULL,
-            created_at INTEGER NOT NULL,
-            PRIMARY KEY(outlet_id, press_role)
-        );
-
-        CREATE TABLE IF NOT EXISTS wallet_links (
-            discord_user_id TEXT PRIMARY KEY,
-            wallet_address TEXT NOT NULL,
-            created_at INTEGER NOT NULL
-        );
+const MIGRATIONS: &[press_migrate::Migration] = &[
+    press_migrate::Migration { version: 1, name: "baseline", sql: include_str!("../migrations/001_baseline.sql") },
+    press_migrate::Migration { version: 2, name: "council_count_cache", sql: include_str!("../migrations/002_council_count_cache.sql") },
+];
 
-        CREATE TABLE IF NOT EXISTS oauth_sessions (
-            session_id TEXT PRIMARY KEY,
-            discord_user_id TEXT NOT NULL,
-            outlet_id TEXT,
-            created_at INTEGER NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS council_grace (
-            discord_user_id TEXT PRIMARY KEY,
-            lost_at INTEGER NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS council_members (
-            wallet_address TEXT PRIMARY KEY,
-            joined_at INTEGER NOT NULL,
-            last_active_at INTEGER NOT NULL
-        );
-    "#).execute(db).await?;
+// Applies pending migrations; refuses to start when bots.db has drifted from this build.
+async fn init_db(db: &sqlx::AnyPool) -> anyhow::Result<()> {
+    press_migrate::run(db, MIGRATIONS).await.map_err(anyhow::Error::msg)?;
     Ok(())
 }
 
+async fn get_migrations(axum::extract::State(state): axum::extract::State<AppState>) -> Json<serde_json::Value> {
+    let s = press_migrate::status(&state.db, MIGRATIONS).await;
+    Json(serde_json::json!({"ok": s.ok(), "migrations": s}))
+}
+
 async fn council_status(
     axum::extract::State(state): axum::extract::State<AppState>,
 ) -> Json<serde_json::Value> {
@@ -11,6 +11,7 @@ use tower_http::cors::CorsLayer;
 use uuid::Uuid;
 
 use std::process::Command;
+use press_metrics::RouterExt;
 
 fn sh(step: &str, engine: &Engine, cmd: &str) -> Result<String, String> {
     engine.write_log(step, &format!("$ {}", cmd));
@@ -293,11 +294,20 @@ impl Engine {
     }
 
     fn end_step(&self, s: &mut RunState, id: &str, status: StepStatus, err: Option<String>) {
+        let result = match status {
+            StepStatus::Success => "success",
+            StepStatus::Fail => "fail",
+            StepStatus::Skipped => "skipped",
+            StepStatus::Pending | StepStatus::Running => "incomplete",
+        };
         for st in &mut s.steps {
             if st.id == id {
                 st.status = status;
                 st.ended_at = Some(Self::now());
                 st.error = err;
+                if let (Some(a), Some(b)) = (st.started_at, st.ended_at) {
+                    press_metrics::deployer_step(id, result, b.saturating_sub(a) as f64);
+                }
             }
         }
         s.updated_at = Self::now();
@@ -834,6 +844,7 @@ async fn main() {
         .route("/installer/retry/:step", post(retry))
         .route("/installer/fix/:fix_id", post(fix))
         .route("/installer/output", get(output))
+        .with_metrics("deployer_api")
         .layer(CorsLayer::permissive())
         .with_state(app_state);
 
@@ -1,5 +1,3 @@
-static REQUESTS: AtomicU64 = AtomicU64::new(0);
-static ERRORS: AtomicU64 = AtomicU64::new(0);
 
 
 
@@ -72,10 +70,11 @@ fn module_enabled(key: &str) -> bool {
 
 use axum::{routing::{get, post}, Json, Router, extract::State};
 use serde::{Deserialize, Serialize};
-use std::{sync::atomic::{AtomicU64, Ordering}, net::SocketAddr, path::PathBuf, fs};
+use std::{net::SocketAddr, path::PathBuf, fs};
 use tower_http::cors::{CorsLayer, Any};
 use tracing::info;
 use ethers::{prelude::*, types::U256};
+use press_metrics::RouterExt;
 
 static VERSION: &str = "RR109";
 const STANDARD_OUTLET_TOKEN_SUPPLY: u128 = 1_000_000_000_000000000000000000u128; // 1e9 * 1e18
@@ -678,9 +677,11 @@ async fn ops_metrics() -> axum::Json<serde_json::Value> {
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_secs())
         .unwrap_or(0);
+    // the same counters /metrics exposes; errors are 5xx responses
+    let (requests, errors) = press_metrics::http_totals();
     axum::Json(serde_json::json!({
-        "requests": REQUESTS.load(Ordering::Relaxed),
-        "errors": ERRORS.load(Ordering::Relaxed),
+        "requests": requests,
+        "errors": errors,
         "epoch": up
     }))
 }
@@ -751,7 +752,7 @@ fn main() {
     let st = AppState{ state_dir: state_dir(), rpc_url: rpc_url_from_env() };
     let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
 
-    let app = Router::new().layer(rate_limit::layer()).layer(track_requests())
+    let app = Router::new().layer(rate_limit::layer())
         .route("/health", get(health))
         .route("/api/outlet/info", get(wizard_info))
         .route("/api/outlets/create", post(create_outlet))
@@ -768,6 +769,7 @@ fn main() {
         .route("/api/deploy/snapshot", get(deploy_snapshot))
         .route("/api/deploy/snapshot/write", post(write_deploy_snapshot))
         .route("/api/deploy/auto-fix", post(run_auto_fix))
+        .with_metrics("gateway_api")
 
         .layer(cors)
         .with_state(st);
@@ -0,0 +1,490 @@
+-- Postgres version of sqlite/001_baseline.sql: the same tables, columns and indexes, so query_api
+-- can read what the indexer writes. Differences are limited to types (BIGSERIAL ids, BIGINT for
+-- SQLite INTEGER) and the search index, which is a tsvector table kept current by triggers instead
+-- of FTS5. Recipe tables are created at runtime from the recipe files.
+
+CREATE TABLE IF NOT EXISTS meta (
+    k TEXT PRIMARY KEY,
+    v TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS stream_checkpoints (
+    stream TEXT PRIMARY KEY,
+    last_block BIGINT NOT NULL DEFAULT 0,
+    finalized_block BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    last_error_at TEXT,
+    last_success_at TEXT,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS indexed_blocks (
+    stream TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    block_hash TEXT NOT NULL,
+    parent_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    recorded_at TEXT NOT NULL,
+    PRIMARY KEY (stream, block_number)
+);
+
+-- core stream
+
+CREATE TABLE IF NOT EXISTS outlets (
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    name TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    bond_paid TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    inserted_at TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS outlet_tokens (
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    token TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    name TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    supply TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    inserted_at TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS token_listings (
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    token TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    tier BIGINT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    perks TEXT NOT NULL,
+    inserted_at TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS outlet_domain_verifications (
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    proof_type BIGINT NOT NULL,
+    proof_hash TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    inserted_at TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS heartbeats (
+    id BIGSERIAL PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    service TEXT NOT NULL,
+    ts BIGINT NOT NULL,
+    status BIGINT NOT NULL,
+    extra TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_heartbeats_service_ts ON heartbeats(service, ts);
+
+CREATE TABLE IF NOT EXISTS outlet_role_events (
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    account TEXT NOT NULL,
+    role TEXT NOT NULL,
+    event TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_outlet_role_events_account ON outlet_role_events(account);
+
+CREATE TABLE IF NOT EXISTS outlet_domain_checks (
+    outlet_id TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    dns_ok BOOLEAN NOT NULL,
+    http_ok BOOLEAN NOT NULL,
+    notes TEXT NOT NULL,
+    checked_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS token_tests (
+    token TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    status BIGINT NOT NULL,
+    tested_at TEXT NOT NULL
+);
+
+-- exchange registry stream
+
+CREATE TABLE IF NOT EXISTS exchange_listings (
+    outlet TEXT PRIMARY KEY,
+    tier TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    test_passed BIGINT NOT NULL,
+    listed_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- governance and upgrade queue streams
+
+CREATE TABLE IF NOT EXISTS governance_proposals (
+    proposal_id BIGINT PRIMARY KEY,
+    proposer TEXT NOT NULL,
+    title TEXT NOT NULL,
+    config_key TEXT NOT NULL,
+    config_value TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    ends_at TEXT NOT NULL,
+    block_number BIGINT NOT NULL DEFAULT 0,
+    finalized BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS approved_updates (
+    proposal_id BIGINT NOT NULL,
+    config_key TEXT NOT NULL,
+    config_value TEXT NOT NULL,
+    passed BIGINT NOT NULL,
+    auto_applied BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    recorded_at TEXT NOT NULL,
+    block_number BIGINT NOT NULL DEFAULT 0,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    tx_hash TEXT NOT NULL DEFAULT '',
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS governance_vote_fees (
+    tx_hash TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block_num BIGINT NOT NULL,
+    created_at TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS governance_grants (
+    tx_hash TEXT NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    recipient TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block_num BIGINT NOT NULL,
+    created_at TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+
+CREATE TABLE IF NOT EXISTS release_batches (
+    batch_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    window_start TEXT NOT NULL,
+    window_end TEXT NOT NULL,
+    status TEXT NOT NULL,
+    notes TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS release_batch_items (
+    batch_id TEXT NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    config_key TEXT NOT NULL,
+    config_value TEXT NOT NULL,
+    status TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    block_number BIGINT NOT NULL DEFAULT 0,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    UNIQUE (batch_id, proposal_id)
+);
+
+-- article events (POST /events and the article approvals stream) and oracle flags
+
+CREATE TABLE IF NOT EXISTS press_events (
+    id BIGSERIAL PRIMARY KEY,
+    ts BIGINT NOT NULL,
+    article_id TEXT NOT NULL,
+    outlet TEXT NOT NULL,
+    event TEXT NOT NULL,
+    title TEXT,
+    url TEXT,
+    canonical_text TEXT,
+    content_hash TEXT,
+    metadata TEXT NOT NULL,
+    block_number BIGINT NOT NULL DEFAULT 0,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    tx_hash TEXT NOT NULL DEFAULT '',
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_events_ts ON press_events(ts);
+CREATE INDEX IF NOT EXISTS idx_events_article ON press_events(article_id);
+
+CREATE TABLE IF NOT EXISTS oracle_flags (
+    id BIGSERIAL PRIMARY KEY,
+    ts BIGINT NOT NULL,
+    article_id TEXT NOT NULL,
+    severity BIGINT NOT NULL,
+    kind TEXT NOT NULL,
+    source TEXT NOT NULL,
+    title TEXT,
+    url TEXT,
+    details TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_flags_ts ON oracle_flags(ts);
+CREATE INDEX IF NOT EXISTS idx_flags_article ON oracle_flags(article_id);
+
+CREATE TABLE IF NOT EXISTS article_vote_windows (
+    article_id TEXT NOT NULL,
+    start_at BIGINT NOT NULL,
+    end_at BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_article_windows_article ON article_vote_windows(article_id);
+
+CREATE TABLE IF NOT EXISTS article_votes (
+    article_id TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    approve BIGINT NOT NULL,
+    bucket BIGINT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_article_votes_article ON article_votes(article_id);
+
+CREATE TABLE IF NOT EXISTS article_vote_results (
+    article_id TEXT NOT NULL,
+    approved BIGINT NOT NULL,
+    community BIGINT NOT NULL,
+    outlet BIGINT NOT NULL,
+    council BIGINT NOT NULL,
+    flags BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_article_results_article ON article_vote_results(article_id);
+
+-- disputes stream
+
+CREATE TABLE IF NOT EXISTS court_cases (
+    case_id TEXT NOT NULL,
+    outlet_id TEXT NOT NULL,
+    filed_by TEXT NOT NULL,
+    case_type TEXT NOT NULL,
+    evidence_uri TEXT NOT NULL,
+    case_hash TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_court_cases_case ON court_cases(case_id);
+
+CREATE TABLE IF NOT EXISTS disputes (
+    dispute_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    article_id TEXT NOT NULL,
+    filer TEXT NOT NULL,
+    bond TEXT NOT NULL,
+    reason_uri TEXT NOT NULL,
+    severity BIGINT NOT NULL,
+    confidence_bps BIGINT NOT NULL,
+    model_hash TEXT NOT NULL,
+    evidence_hash TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_disputes_article ON disputes(article_id);
+
+CREATE TABLE IF NOT EXISTS dispute_events (
+    id BIGSERIAL PRIMARY KEY,
+    source TEXT NOT NULL,
+    event TEXT NOT NULL,
+    case_id TEXT,
+    dispute_id TEXT,
+    article_id TEXT,
+    case_ref TEXT,
+    details TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_dispute_events_case ON dispute_events(case_id);
+CREATE INDEX IF NOT EXISTS idx_dispute_events_dispute ON dispute_events(dispute_id);
+
+-- treasury stream
+
+CREATE TABLE IF NOT EXISTS treasury_ledger (
+    id BIGSERIAL PRIMARY KEY,
+    entry_id TEXT NOT NULL,
+    leg TEXT NOT NULL,
+    account TEXT NOT NULL,
+    counterparty TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    flow TEXT NOT NULL,
+    category TEXT NOT NULL,
+    event TEXT NOT NULL,
+    contract TEXT NOT NULL,
+    context TEXT NOT NULL,
+    ref TEXT NOT NULL,
+    block_time BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_treasury_ledger_account ON treasury_ledger(account, asset);
+CREATE INDEX IF NOT EXISTS idx_treasury_ledger_block ON treasury_ledger(block_number, log_index);
+
+-- council stream
+
+CREATE TABLE IF NOT EXISTS council_bond_events (
+    wallet TEXT NOT NULL,
+    role TEXT NOT NULL,
+    event TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block_time BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_council_bond_events_wallet ON council_bond_events(wallet);
+
+CREATE TABLE IF NOT EXISTS council_membership (
+    wallet TEXT NOT NULL,
+    event TEXT NOT NULL,
+    term_start BIGINT NOT NULL,
+    term_end BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    block_time BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_council_membership_wallet ON council_membership(wallet);
+
+CREATE TABLE IF NOT EXISTS council_actions (
+    wallet TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    ref TEXT NOT NULL,
+    ts BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_council_actions_wallet_ts ON council_actions(wallet, ts);
+
+-- full-text search over articles and proposals
+
+CREATE TABLE IF NOT EXISTS search_fts (
+    kind TEXT NOT NULL,
+    ref_id BIGINT NOT NULL,
+    article_id TEXT NOT NULL,
+    outlet TEXT NOT NULL,
+    event TEXT NOT NULL,
+    ts BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    doc TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', title), 'A') ||
+        setweight(to_tsvector('simple', outlet), 'B') ||
+        setweight(to_tsvector('simple', body), 'C')
+    ) STORED
+);
+CREATE INDEX IF NOT EXISTS idx_search_fts_doc ON search_fts USING GIN (doc);
+CREATE INDEX IF NOT EXISTS idx_search_fts_ref ON search_fts(kind, ref_id);
+
+-- upserts fire UPDATE rather than DELETE + INSERT, so both rewrite the entry
+CREATE OR REPLACE FUNCTION search_press_events_sync() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        DELETE FROM search_fts WHERE kind='article' AND ref_id=OLD.id;
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        INSERT INTO search_fts (kind, ref_id, article_id, outlet, event, ts, title, body)
+        VALUES ('article', NEW.id, NEW.article_id, NEW.outlet, NEW.event, NEW.ts, COALESCE(NEW.title,''), COALESCE(NEW.canonical_text,''));
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION search_proposals_sync() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        DELETE FROM search_fts WHERE kind='proposal' AND ref_id=OLD.proposal_id;
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        INSERT INTO search_fts (kind, ref_id, article_id, outlet, event, ts, title, body)
+        VALUES ('proposal', NEW.proposal_id, '', '', 'ProposalCreated', CAST(NEW.created_at AS BIGINT), NEW.title, '');
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS search_press_events_sync ON press_events;
+CREATE TRIGGER search_press_events_sync AFTER INSERT OR UPDATE OR DELETE ON press_events
+    FOR EACH ROW EXECUTE FUNCTION search_press_events_sync();
+
+DROP TRIGGER IF EXISTS search_proposals_sync ON governance_proposals;
+CREATE TRIGGER search_proposals_sync AFTER INSERT OR UPDATE OR DELETE ON governance_proposals
+    FOR EACH ROW EXECUTE FUNCTION search_proposals_sync();
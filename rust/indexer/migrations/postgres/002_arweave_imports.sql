@@ -0,0 +1,66 @@
+-- arweave_imports stream: LegacyMigrationEngine jobs, PressArweaveImportRegistry registrations and
+-- import policy changes, plus the verifier's result per completed job.
+
+CREATE TABLE IF NOT EXISTS arweave_import_jobs (
+    import_id TEXT NOT NULL,
+    event TEXT NOT NULL,
+    requester TEXT NOT NULL,
+    item_count BIGINT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    bond_locked TEXT NOT NULL,
+    manifest_uri TEXT NOT NULL,
+    root_hash TEXT NOT NULL,
+    anchored_count BIGINT NOT NULL,
+    block_time BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_arweave_import_jobs_id ON arweave_import_jobs(import_id);
+
+CREATE TABLE IF NOT EXISTS arweave_imports (
+    importer TEXT NOT NULL,
+    article_id TEXT NOT NULL,
+    arweave_tx_id TEXT NOT NULL,
+    arweave_tx_id_hash TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    bond_required TEXT NOT NULL,
+    block_time BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_arweave_imports_hash ON arweave_imports(arweave_tx_id_hash);
+CREATE INDEX IF NOT EXISTS idx_arweave_imports_article ON arweave_imports(article_id);
+CREATE INDEX IF NOT EXISTS idx_arweave_imports_importer ON arweave_imports(importer);
+
+CREATE TABLE IF NOT EXISTS arweave_import_policy (
+    fee TEXT NOT NULL,
+    bond TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS arweave_import_verifications (
+    import_id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    anchored_root TEXT NOT NULL,
+    computed_root TEXT NOT NULL,
+    anchored_count BIGINT NOT NULL,
+    manifest_items BIGINT NOT NULL,
+    registered_items BIGINT NOT NULL,
+    missing TEXT NOT NULL,
+    error TEXT NOT NULL,
+    completed_tx TEXT NOT NULL,
+    checked_at TEXT NOT NULL
+);
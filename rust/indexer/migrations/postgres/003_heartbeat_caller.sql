@@ -0,0 +1,2 @@
+-- UptimeBeacon.heartbeat can be called by anyone; keep who sent each heartbeat.
+ALTER TABLE heartbeats ADD COLUMN caller TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,28 @@
+-- Release batches: one row per batch id (ensure_release_batch used to insert a row per call),
+-- the upgrade queue's bytes32 batch id on queued items, and ReleaseBatchManager events.
+
+DELETE FROM release_batches a USING release_batches b WHERE a.batch_id = b.batch_id AND a.ctid > b.ctid;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_release_batches_batch ON release_batches(batch_id);
+
+ALTER TABLE release_batch_items ADD COLUMN queue_batch_id TEXT NOT NULL DEFAULT '';
+
+CREATE TABLE IF NOT EXISTS release_batch_events (
+    manager_batch_id BIGINT NOT NULL,
+    event TEXT NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    proposer TEXT NOT NULL,
+    p_type BIGINT NOT NULL,
+    label TEXT NOT NULL,
+    min_ready_at BIGINT NOT NULL,
+    release_tag TEXT NOT NULL,
+    notes_cid TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    event_time BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_release_batch_events_batch ON release_batch_events(manager_batch_id);
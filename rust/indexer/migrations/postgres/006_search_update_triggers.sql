@@ -0,0 +1,3 @@
+-- Keeps the numbering in step with sqlite/006_search_update_triggers.sql. Nothing to do here: the
+-- Postgres search_fts triggers in 001_baseline already fire on UPDATE as well as INSERT and DELETE.
+SELECT 1;
@@ -0,0 +1,25 @@
+-- Items PressUpgradeQueue queued (BatchQueued) get their own table, owned by the upgrade_queue
+-- stream; release_batch_items keeps the items governance plans, owned by the governance stream. Each
+-- stream then rolls back and finalizes only rows it can replay. Queued rows written into
+-- release_batch_items before the split are moved over and their planned row restored.
+
+CREATE TABLE IF NOT EXISTS release_batch_queue_items (
+    batch_id TEXT NOT NULL,
+    queue_batch_id TEXT NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    config_key TEXT NOT NULL,
+    config_value TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_release_batch_queue_items_batch ON release_batch_queue_items(batch_id);
+
+INSERT INTO release_batch_queue_items (batch_id, queue_batch_id, proposal_id, config_key, config_value, block_number, log_index, tx_hash, finalized, inserted_at, chain_id)
+SELECT batch_id, queue_batch_id, proposal_id, config_key, config_value, block_number, -1, '', finalized, created_at, 0
+FROM release_batch_items WHERE status = 'queued';
+
+UPDATE release_batch_items SET status = 'planned', queue_batch_id = '' WHERE status = 'queued';
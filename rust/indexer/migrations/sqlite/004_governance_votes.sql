@@ -0,0 +1,20 @@
+-- governance stream: one row per PressGovernance VoteCast, and the tallies ProposalFinalized reports.
+
+CREATE TABLE IF NOT EXISTS governance_votes (
+    proposal_id BIGINT NOT NULL,
+    voter TEXT NOT NULL,
+    support BIGINT NOT NULL,
+    weight TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_governance_votes_proposal ON governance_votes(proposal_id);
+CREATE INDEX IF NOT EXISTS idx_governance_votes_voter ON governance_votes(voter);
+
+ALTER TABLE approved_updates ADD COLUMN yes_votes TEXT NOT NULL DEFAULT '0';
+ALTER TABLE approved_updates ADD COLUMN no_votes TEXT NOT NULL DEFAULT '0';
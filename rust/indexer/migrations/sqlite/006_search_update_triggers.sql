@@ -0,0 +1,17 @@
+-- Upserts now update rows in place (ON CONFLICT ... DO UPDATE) instead of INSERT OR REPLACE, so a
+-- re-read event keeps its id and search_fts is refreshed by update triggers. REPLACE deleted rows
+-- without firing search_press_events_ad; the entries it left behind are dropped here.
+
+CREATE TRIGGER IF NOT EXISTS search_press_events_au AFTER UPDATE ON press_events BEGIN
+    DELETE FROM search_fts WHERE kind='article' AND ref_id=old.id;
+    INSERT INTO search_fts (kind, ref_id, article_id, outlet, event, ts, title, body)
+    VALUES ('article', new.id, new.article_id, new.outlet, new.event, new.ts, COALESCE(new.title,''), COALESCE(new.canonical_text,''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_proposals_au AFTER UPDATE ON governance_proposals BEGIN
+    DELETE FROM search_fts WHERE kind='proposal' AND ref_id=old.proposal_id;
+    INSERT INTO search_fts (kind, ref_id, article_id, outlet, event, ts, title, body)
+    VALUES ('proposal', new.proposal_id, '', '', 'ProposalCreated', CAST(new.created_at AS INTEGER), new.title, '');
+END;
+
+DELETE FROM search_fts WHERE kind='article' AND ref_id NOT IN (SELECT id FROM press_events);
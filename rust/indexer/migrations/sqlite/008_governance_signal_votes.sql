@@ -0,0 +1,20 @@
+-- council stream: one row per PressGovernanceSignals ProposalVoted, laid out like governance_votes so
+-- tallies can read both. support is the raw uint8 (0 against, 1 for, 2 abstain); the event carries no
+-- weight, so every signal counts as weight 1. Signals already stored only as council_actions are
+-- picked up by backfilling the council stream.
+
+CREATE TABLE IF NOT EXISTS governance_signal_votes (
+    proposal_id BIGINT NOT NULL,
+    voter TEXT NOT NULL,
+    support BIGINT NOT NULL,
+    weight TEXT NOT NULL,
+    fee_paid TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    finalized BIGINT NOT NULL DEFAULT 0,
+    inserted_at TEXT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 0,
+    log_index BIGINT NOT NULL DEFAULT -1
+);
+CREATE INDEX IF NOT EXISTS idx_governance_signal_votes_proposal ON governance_signal_votes(proposal_id);
+CREATE INDEX IF NOT EXISTS idx_governance_signal_votes_voter ON governance_signal_votes(voter);
@@ -0,0 +1,5 @@
+-- Reorg rollbacks per stream: how many there have been and the last fork block. Consumers that cache
+-- derived state compare the count between polls, since a rollback need not lower MAX(block_number).
+
+ALTER TABLE stream_checkpoints ADD COLUMN rollbacks BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE stream_checkpoints ADD COLUMN last_fork BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,282 @@
+// Backfill / replay of block ranges.
+//
+// The live loops start a couple of thousand blocks behind head, so older history only reaches the
+// DB through here. A job re-indexes [from, to] for the selected streams in chunks, into the live DB
+// or a scratch SQLite file. Chunks go through the same idempotent upserts as the live loops, so
+// running the same job twice leaves the same rows behind and replayed rows keep their ids (feed and
+// SSE cursors). `rebuild` replays every stream from the deploy block recorded in /state/deploy.json.
+
+use crate::{
+    ingest_core_range, ingest_deploy_stream_range, ingest_exchange_range, ingest_governance_range, ingest_upgrade_queue_range, upgrade_queue_addresses,
+    checkpoints, dedupe, meta_set, now_iso, prepare_db, read_state_string, recipes, store, stream_tables,
+    AppState, STREAMS, STREAM_ARTICLES, STREAM_CORE, STREAM_COUNCIL, STREAM_DISPUTES, STREAM_EXCHANGE, STREAM_GOVERNANCE, STREAM_IMPORTS, STREAM_TREASURY, STREAM_UPGRADE_QUEUE,
+};
+use crate::getlogs::{is_limit_error, Window};
+use ethers::prelude::*;
+use ethers::types::{Address, U64};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize, Clone)]
+pub struct BackfillJob {
+    pub id: String,
+    pub streams: Vec<String>,
+    pub from: i64,
+    pub to: i64,
+    // scratch SQLite file; None writes into the live DB
+    pub db: Option<String>,
+    pub status: String,
+    // streams left out because their live loop has no cursor yet (live DB only)
+    pub skipped: Vec<String>,
+    pub stream: String,
+    pub current_block: i64,
+    pub blocks_done: i64,
+    pub blocks_total: i64,
+    pub logs: i64,
+    pub percent: f64,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+pub type Jobs = Arc<Mutex<Vec<BackfillJob>>>;
+
+#[derive(Deserialize, Default)]
+pub struct BackfillReq {
+    #[serde(default)]
+    pub streams: Vec<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub db: Option<String>,
+    // replay everything from the deploy block; `from` is ignored
+    #[serde(default)]
+    pub rebuild: bool,
+}
+
+fn chunk_size() -> i64 {
+    std::env::var("INDEXER_BACKFILL_CHUNK").ok().and_then(|s| s.parse::<i64>().ok()).filter(|n| *n > 0).unwrap_or(2000)
+}
+
+pub fn known_streams(st: &AppState) -> Vec<String> {
+    let mut out: Vec<String> = STREAMS.iter().map(|s| s.to_string()).collect();
+    for r in st.recipes.iter() { out.push(format!("recipe:{}", r.name)); }
+    out
+}
+
+// deployBlock (or startBlock) from /state/deploy.json, as a number or hex string.
+pub fn deploy_block() -> Result<i64, String> {
+    let deploy: serde_json::Value = std::fs::read_to_string("/state/deploy.json").ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .ok_or_else(|| "cannot read /state/deploy.json".to_string())?;
+    let v = deploy.get("deployBlock").or_else(|| deploy.get("startBlock"))
+        .ok_or_else(|| "deploy.json has no deployBlock".to_string())?;
+    v.as_i64()
+        .or_else(|| v.as_str().and_then(|s| match s.strip_prefix("0x") {
+            Some(h) => i64::from_str_radix(h, 16).ok(),
+            None => s.parse::<i64>().ok(),
+        }))
+        .ok_or_else(|| format!("invalid deployBlock {}", v))
+}
+
+fn state_address(path: &str) -> Option<Address> {
+    read_state_string(path).and_then(|s| s.parse::<Address>().ok()).filter(|a| *a != Address::zero())
+}
+
+// Last block the live loop of `stream` has processed. Replays into the live DB stop there so the
+// loop does not write the same blocks a second time.
+async fn live_cursor(st: &AppState, stream: &str) -> Option<i64> {
+    checkpoints::cursor(&st.db, stream).await
+}
+
+fn tables_of(st: &AppState, stream: &str) -> Vec<(String, String)> {
+    if let Some(name) = stream.strip_prefix("recipe:") {
+        return st.recipes.iter().filter(|r| r.name == name)
+            .flat_map(|r| recipes::recipe_tables(r).into_iter().map(|(t, c)| (t.to_string(), c.to_string())).collect::<Vec<_>>())
+            .collect();
+    }
+    stream_tables(stream).iter().map(|(t, c)| (t.to_string(), c.to_string())).collect()
+}
+
+// Drops only what the replay cannot rewrite in place: event rows stored before they were keyed
+// (log_index -1), which the keyed copies would otherwise duplicate. Keyed rows are updated by the
+// upserts, and rows of other streams or from outside the chain are never touched.
+async fn clear_range(st: &AppState, stream: &str, from: i64, to: i64) {
+    // block 0 marks rows that did not come from the chain (e.g. POST /events); never touch those
+    let from = from.max(1);
+    for (table, col) in tables_of(st, stream) {
+        if !dedupe::EVENT_TABLES.iter().any(|(t, _, _)| *t == table) { continue; }
+        let _ = sqlx::query(&format!("DELETE FROM {} WHERE {} >= $1 AND {} <= $2 AND log_index < 0", table, col, col))
+            .bind(from).bind(to).execute(&st.db).await;
+    }
+}
+
+async fn ingest_chunk(st: &AppState, provider: &Provider<Http>, stream: &str, from: i64, to: i64) -> Result<usize, String> {
+    let (f, t) = (U64::from(from as u64), U64::from(to as u64));
+    match stream {
+        STREAM_CORE => ingest_core_range(st, from, to).await,
+        STREAM_GOVERNANCE => {
+            let a = state_address("/state/press_governance_address.txt").ok_or("missing governance address")?;
+            ingest_governance_range(st, provider, a, f, t).await
+        }
+        STREAM_UPGRADE_QUEUE => {
+            let addrs = upgrade_queue_addresses();
+            if addrs.is_empty() { return Err("missing upgrade queue and release batch manager addresses".into()); }
+            ingest_upgrade_queue_range(st, provider, &addrs, f, t).await
+        }
+        STREAM_EXCHANGE => {
+            let a = state_address("/state/exchange_listing_registry_address.txt").ok_or("missing exchange registry address")?;
+            ingest_exchange_range(st, provider, a, f, t).await
+        }
+        STREAM_ARTICLES | STREAM_DISPUTES | STREAM_TREASURY | STREAM_COUNCIL | STREAM_IMPORTS => ingest_deploy_stream_range(st, provider, stream, f, t).await,
+        s => {
+            let name = s.strip_prefix("recipe:").ok_or_else(|| format!("unknown stream {}", s))?;
+            let r = st.recipes.iter().find(|r| r.name == name).ok_or_else(|| format!("unknown recipe {}", name))?;
+            recipes::ensure_tables(st, r).await;
+            recipes::ingest_range(st, r, provider, from as u64, to as u64).await
+        }
+    }
+}
+
+// Marks replayed rows below the confirmation depth as final. Unlike finalize_stream this does not
+// move the stream's finalized_block marker, which belongs to the live loop.
+async fn finalize_range(st: &AppState, stream: &str, from: i64, to: i64, head: i64) {
+    let fin = (head - st.confirmations).min(to);
+    if fin < from { return; }
+    for (table, col) in tables_of(st, stream) {
+        let _ = sqlx::query(&format!("UPDATE {} SET finalized=1 WHERE finalized=0 AND {} >= $1 AND {} <= $2", table, col, col))
+            .bind(from).bind(fin).execute(&st.db).await;
+    }
+    let _ = sqlx::query("DELETE FROM indexed_blocks WHERE stream=$1 AND block_number >= $2 AND block_number <= $3 AND block_number < (SELECT MAX(block_number) FROM indexed_blocks WHERE stream=$1)")
+        .bind(stream).bind(from).bind(fin).execute(&st.db).await;
+}
+
+// Opens the scratch DB when one is given; schema is created the same way as for the live DB.
+pub async fn target_state(st: &AppState, db: Option<&str>) -> Result<AppState, String> {
+    let Some(path) = db.filter(|p| !p.is_empty()) else { return Ok(st.clone()) };
+    let url = if path.starts_with("sqlite:") { path.to_string() } else { format!("sqlite://{}?mode=rwc", path) };
+    let pool = store::connect(&url).await.map_err(|e| format!("open {}: {}", path, e))?;
+    prepare_db(&pool).await.map_err(|e| format!("{}: {}", path, e))?;
+    Ok(AppState { db: pool, ..st.clone() })
+}
+
+// Validates a request and registers the job; the caller runs it with `run`.
+pub async fn create_job(st: &AppState, req: BackfillReq) -> Result<BackfillJob, String> {
+    let known = known_streams(st);
+    let streams = if req.streams.is_empty() { known.clone() } else { req.streams.clone() };
+    if let Some(bad) = streams.iter().find(|s| !known.contains(s)) {
+        return Err(format!("unknown stream {}", bad));
+    }
+    let from = if req.rebuild { deploy_block()? } else { req.from.ok_or("from is required")? };
+    let to = match req.to {
+        Some(n) => n,
+        None => {
+            let provider = Provider::<Http>::try_from(st.rpc_http.clone()).map_err(|e| e.to_string())?;
+            provider.get_block_number().await.map_err(|e| e.to_string())?.as_u64() as i64
+        }
+    };
+    if from < 0 || to < from { return Err(format!("invalid range [{}, {}]", from, to)); }
+
+    let job = BackfillJob {
+        id: format!("bf-{}", chrono::Utc::now().timestamp_millis()),
+        streams,
+        from,
+        to,
+        db: req.db.filter(|p| !p.is_empty()),
+        status: "queued".into(),
+        skipped: vec![],
+        stream: String::new(),
+        current_block: from,
+        blocks_done: 0,
+        blocks_total: 0,
+        logs: 0,
+        percent: 0.0,
+        error: None,
+        started_at: now_iso(),
+        finished_at: None,
+    };
+    st.backfills.lock().await.push(job.clone());
+    Ok(job)
+}
+
+async fn update(st: &AppState, id: &str, f: impl FnOnce(&mut BackfillJob)) -> Option<BackfillJob> {
+    let mut jobs = st.backfills.lock().await;
+    let job = jobs.iter_mut().find(|j| j.id == id)?;
+    f(job);
+    Some(job.clone())
+}
+
+pub async fn run(st: AppState, id: String, on_progress: impl Fn(&BackfillJob)) {
+    let Some(job) = update(&st, &id, |j| j.status = "running".into()).await else { return };
+    let result = run_job(&st, &job, &on_progress).await;
+    let done = update(&st, &id, |j| {
+        j.finished_at = Some(now_iso());
+        match result {
+            Ok(()) => { j.status = "done".into(); j.percent = 100.0; }
+            Err(e) => { j.status = "failed".into(); j.error = Some(e); }
+        }
+    }).await;
+    if let Some(j) = done { on_progress(&j); }
+}
+
+async fn run_job(st: &AppState, job: &BackfillJob, on_progress: &impl Fn(&BackfillJob)) -> Result<(), String> {
+    let target = target_state(st, job.db.as_deref()).await?;
+    let live = job.db.is_none();
+    let provider = Provider::<Http>::try_from(st.rpc_http.clone()).map_err(|e| e.to_string())?;
+    let head = provider.get_block_number().await.map_err(|e| e.to_string())?.as_u64() as i64;
+
+    // per-stream upper bound: never past head, never past what the live loop has already processed
+    // (live DB only), and for the exchange stream never into unconfirmed blocks
+    let mut plan: Vec<(String, i64)> = vec![];
+    let mut skipped: Vec<String> = vec![];
+    for s in &job.streams {
+        let mut to = job.to.min(head);
+        if s == STREAM_EXCHANGE { to = to.min(head - st.confirmations); }
+        if live {
+            match live_cursor(st, s).await {
+                Some(c) => to = to.min(c),
+                None => { skipped.push(s.clone()); continue; }
+            }
+        }
+        if to >= job.from { plan.push((s.clone(), to)); }
+    }
+    let total: i64 = plan.iter().map(|(_, to)| to - job.from + 1).sum();
+    update(st, &job.id, |j| { j.blocks_total = total; j.skipped = skipped; }).await;
+
+    let mut done = 0i64;
+    for (stream, to) in &plan {
+        // chunks halve on provider limit errors like the live loops; other errors fail the job
+        let mut window = Window::with_size(&format!("backfill:{}", stream), chunk_size() as u64);
+        let mut from = job.from;
+        while from <= *to {
+            let end = window.end(from as u64, *to as u64) as i64;
+            clear_range(&target, stream, from, end).await;
+            let n = match ingest_chunk(&target, &provider, stream, from, end).await {
+                Ok(n) => n,
+                Err(e) if is_limit_error(&e) && window.size() > 1 => {
+                    window.err(st, *to, from - 1, &e).await;
+                    continue;
+                }
+                Err(e) => return Err(format!("{} [{}, {}]: {}", stream, from, end, e)),
+            };
+            finalize_range(&target, stream, from, end, head).await;
+            window.ok(st, *to, end);
+            done += end - from + 1;
+            if let Some(j) = update(st, &job.id, |j| {
+                j.stream = stream.clone();
+                j.current_block = end;
+                j.blocks_done = done;
+                j.logs += n as i64;
+                j.percent = if total > 0 { (done as f64 * 1000.0 / total as f64).round() / 10.0 } else { 100.0 };
+            }).await { on_progress(&j); }
+            from = end + 1;
+        }
+        meta_set(&target.db, &format!("backfilled:{}", stream), &format!("{}-{}", job.from, to)).await;
+    }
+    Ok(())
+}
+
+pub async fn list(st: &AppState) -> Vec<BackfillJob> {
+    st.backfills.lock().await.clone()
+}
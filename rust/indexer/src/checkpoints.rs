@@ -0,0 +1,164 @@
+// Per-stream checkpoints.
+//
+// One row per ingest stream (built-in streams and recipe:<name>): the last block the stream has
+// processed, the last block marked final, and when it last succeeded or failed. Cursors used to be
+// spread over meta (`last_block`, `last_block:<stream>`, `finalized_block:<stream>`) and
+// /state/indexer_*_lastblock.txt files; `import_legacy` copies those over once on startup.
+// /streams reports every stream's lag behind head and flags streams that stopped advancing.
+
+use crate::store::Db;
+use crate::{now_iso, read_state_string, rpc_call, AppState};
+use axum::{extract::State, Json};
+use serde_json::json;
+use sqlx::Row;
+
+async fn import(db: &Db, stream: &str, last_block: i64) {
+    if last_block <= 0 { return; }
+    // counted as a success so imported streams get the full stall grace period after a restart
+    let _ = sqlx::query("INSERT INTO stream_checkpoints (stream, last_block, last_success_at, updated_at) VALUES ($1,$2,$3,$3) ON CONFLICT (stream) DO NOTHING")
+        .bind(stream).bind(last_block).bind(now_iso()).execute(db).await;
+}
+
+// Live DB only: the files belong to the running indexer, not to scratch backfill DBs.
+pub async fn import_legacy(db: &Db) {
+    let meta = sqlx::query("SELECT k, v FROM meta WHERE k='last_block' OR k LIKE 'last_block:%' OR k LIKE 'finalized_block:%'")
+        .fetch_all(db).await.unwrap_or_default();
+    let mut finalized = vec![];
+    for r in &meta {
+        let k: String = r.get("k");
+        let Ok(n) = r.get::<String,_>("v").parse::<i64>() else { continue };
+        if k == "last_block" {
+            import(db, crate::STREAM_CORE, n).await;
+        } else if let Some(s) = k.strip_prefix("last_block:") {
+            import(db, s, n).await;
+        } else if let Some(s) = k.strip_prefix("finalized_block:") {
+            finalized.push((s.to_string(), n));
+        }
+    }
+    // these files held the next block to read
+    for (stream, path) in [
+        (crate::STREAM_GOVERNANCE, "/state/indexer_governance_lastblock.txt"),
+        (crate::STREAM_UPGRADE_QUEUE, "/state/indexer_upgradequeue_lastblock.txt"),
+        (crate::STREAM_EXCHANGE, "/state/indexer_exchange_lastblock.txt"),
+    ] {
+        if let Some(n) = read_state_string(path).and_then(|s| s.parse::<i64>().ok()) {
+            import(db, stream, n - 1).await;
+        }
+    }
+    for (stream, n) in finalized {
+        let _ = sqlx::query("UPDATE stream_checkpoints SET finalized_block=$2 WHERE stream=$1 AND finalized_block=0")
+            .bind(&stream).bind(n).execute(db).await;
+    }
+}
+
+// Last block `stream` has processed; None until it has processed one.
+pub async fn cursor(db: &Db, stream: &str) -> Option<i64> {
+    sqlx::query("SELECT last_block FROM stream_checkpoints WHERE stream=$1").bind(stream)
+        .fetch_optional(db).await.ok().flatten()
+        .map(|r| r.get::<i64,_>("last_block"))
+        .filter(|n| *n > 0)
+}
+
+// A range up to `block` was ingested.
+pub async fn advance(db: &Db, stream: &str, block: i64) {
+    let now = now_iso();
+    let _ = sqlx::query("INSERT INTO stream_checkpoints (stream, last_block, last_success_at, updated_at) VALUES ($1,$2,$3,$3) ON CONFLICT (stream) DO UPDATE SET last_block=excluded.last_block, last_success_at=excluded.last_success_at, updated_at=excluded.updated_at")
+        .bind(stream).bind(block).bind(&now).execute(db).await;
+}
+
+// Cursor moved back after a reorg; not a success.
+pub async fn rewind(db: &Db, stream: &str, block: i64) {
+    let _ = sqlx::query("UPDATE stream_checkpoints SET last_block=$2, finalized_block=CASE WHEN finalized_block < $2 THEN finalized_block ELSE $2 END, updated_at=$3 WHERE stream=$1")
+        .bind(stream).bind(block).bind(now_iso()).execute(db).await;
+}
+
+// A reorg rolled `stream` back to `fork`.
+pub async fn record_rollback(db: &Db, stream: &str, fork: i64) {
+    let _ = sqlx::query("INSERT INTO stream_checkpoints (stream, rollbacks, last_fork, updated_at) VALUES ($1,1,$2,$3) ON CONFLICT (stream) DO UPDATE SET rollbacks=stream_checkpoints.rollbacks+1, last_fork=excluded.last_fork, updated_at=excluded.updated_at")
+        .bind(stream).bind(fork).bind(now_iso()).execute(db).await;
+}
+
+// Rollbacks so far across `streams`; it only grows, so any change means rows were rolled back.
+pub async fn rollbacks(db: &Db, streams: &[&str]) -> i64 {
+    let mut n = 0;
+    for s in streams {
+        n += sqlx::query("SELECT rollbacks FROM stream_checkpoints WHERE stream=$1").bind(*s)
+            .fetch_optional(db).await.ok().flatten()
+            .map(|r| r.get::<i64,_>("rollbacks"))
+            .unwrap_or(0);
+    }
+    n
+}
+
+pub async fn set_finalized(db: &Db, stream: &str, block: i64) {
+    let _ = sqlx::query("UPDATE stream_checkpoints SET finalized_block=CASE WHEN finalized_block > $2 THEN finalized_block ELSE $2 END, updated_at=$3 WHERE stream=$1")
+        .bind(stream).bind(block).bind(now_iso()).execute(db).await;
+}
+
+pub async fn finalized(db: &Db, stream: &str) -> i64 {
+    sqlx::query("SELECT finalized_block FROM stream_checkpoints WHERE stream=$1").bind(stream)
+        .fetch_optional(db).await.ok().flatten()
+        .map(|r| r.get::<i64,_>("finalized_block"))
+        .unwrap_or(0)
+}
+
+pub async fn record_error(db: &Db, stream: &str, err: &str) {
+    let now = now_iso();
+    let _ = sqlx::query("INSERT INTO stream_checkpoints (stream, last_error, last_error_at, updated_at) VALUES ($1,$2,$3,$3) ON CONFLICT (stream) DO UPDATE SET last_error=excluded.last_error, last_error_at=excluded.last_error_at, updated_at=excluded.updated_at")
+        .bind(stream).bind(err).bind(&now).execute(db).await;
+}
+
+fn stall_secs() -> i64 {
+    std::env::var("INDEXER_STALL_SECS").ok().and_then(|s| s.parse::<i64>().ok()).filter(|n| *n > 0).unwrap_or(600)
+}
+
+fn age_secs(ts: &Option<String>) -> Option<i64> {
+    let t = chrono::DateTime::parse_from_rfc3339(ts.as_deref()?).ok()?;
+    Some((chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_seconds())
+}
+
+// A stream is stalled when it is further behind head than the confirmation depth (or has never
+// succeeded) and has not advanced for INDEXER_STALL_SECS. Streams that never ran (no contract
+// configured) are "idle".
+pub async fn streams(State(st): State<AppState>) -> Json<serde_json::Value> {
+    let head_hex = rpc_call(&st.rpc_http, "eth_blockNumber", json!([])).await.ok().and_then(|v| v.as_str().map(|s| s.to_string())).unwrap_or("0x0".into());
+    let head = i64::from_str_radix(head_hex.trim_start_matches("0x"), 16).unwrap_or(0);
+    let rows = sqlx::query("SELECT stream, last_block, finalized_block, last_error, last_error_at, last_success_at FROM stream_checkpoints")
+        .fetch_all(&st.db).await.unwrap_or_default();
+
+    let mut names = crate::backfill::known_streams(&st);
+    for r in &rows {
+        let s: String = r.get("stream");
+        if !names.contains(&s) { names.push(s); }
+    }
+    let stall = stall_secs();
+    let mut stalled = 0;
+    let mut items = vec![];
+    for name in names {
+        let Some(r) = rows.iter().find(|r| r.get::<String,_>("stream") == name) else {
+            items.push(json!({"stream": name, "status": "idle"}));
+            continue;
+        };
+        let last_block: i64 = r.get("last_block");
+        let last_success_at: Option<String> = r.get("last_success_at");
+        let lag = if head > 0 && last_block > 0 { (head - last_block).max(0) } else { 0 };
+        let last_error: Option<String> = r.get("last_error");
+        let since = age_secs(&last_success_at);
+        // a stream that has only ever failed has no cursor to measure lag from
+        let behind = lag > st.confirmations || (last_block == 0 && last_error.is_some());
+        let is_stalled = head > 0 && behind && since.map(|s| s > stall).unwrap_or(true);
+        if is_stalled { stalled += 1; }
+        items.push(json!({
+            "stream": name,
+            "status": if is_stalled { "stalled" } else { "ok" },
+            "last_block": last_block,
+            "finalized_block": r.get::<i64,_>("finalized_block"),
+            "lag": lag,
+            "last_success_at": last_success_at,
+            "seconds_since_success": since,
+            "last_error": last_error,
+            "last_error_at": r.get::<Option<String>,_>("last_error_at"),
+        }));
+    }
+    Json(json!({"ok": head > 0, "head": head, "stall_secs": stall, "stalled": stalled, "streams": items}))
+}
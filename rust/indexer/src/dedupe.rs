@@ -0,0 +1,146 @@
+// Idempotent ingestion.
+//
+// Every event-derived row carries (chain_id, tx_hash, log_index), with a unique index over them, and
+// is written as an upsert (store::event_upsert), so re-reading a block (cursor boundaries, retries, backfills
+// over live ranges) rewrites rows instead of duplicating them. Tables that derive several rows from
+// one log add a discriminator column to the key. Rows written before these columns existed have
+// log_index = -1 and are left out of the (partial) unique index. Duplicates among them and among
+// keyed rows block the index; startup removes them before creating it (as `press_indexer dedupe`
+// does on demand) and refuses to run when an index is still missing, since every upsert into that
+// table would fail.
+
+use crate::store::{self, Db};
+use crate::rpc_call;
+use serde_json::json;
+use sqlx::Row;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+static CHAIN_ID: AtomicI64 = AtomicI64::new(0);
+
+pub fn chain_id() -> i64 { CHAIN_ID.load(Ordering::Relaxed) }
+
+// eth_chainId, falling back to PRESS_CHAIN_ID when the RPC cannot answer at startup.
+pub async fn init_chain_id(rpc: &str) {
+    let from_rpc = rpc_call(rpc, "eth_chainId", json!([])).await.ok()
+        .and_then(|v| v.as_str().and_then(|s| i64::from_str_radix(s.trim_start_matches("0x"), 16).ok()));
+    let id = from_rpc
+        .or_else(|| std::env::var("PRESS_CHAIN_ID").ok().and_then(|s| s.parse::<i64>().ok()))
+        .unwrap_or(0);
+    if id == 0 { eprintln!("dedupe: chain id unknown (no eth_chainId, PRESS_CHAIN_ID unset); rows are keyed with chain_id 0"); }
+    CHAIN_ID.store(id, Ordering::Relaxed);
+}
+
+// (table, block column, extra key columns)
+pub const EVENT_TABLES: &[(&str, &str, &[&str])] = &[
+    ("outlets", "block_number", &[]),
+    ("outlet_tokens", "block_number", &[]),
+    ("token_listings", "block_number", &[]),
+    ("outlet_domain_verifications", "block_number", &[]),
+    ("heartbeats", "block_number", &[]),
+    ("outlet_role_events", "block_number", &[]),
+    ("approved_updates", "block_number", &[]),
+    ("governance_vote_fees", "block_num", &[]),
+    ("governance_grants", "block_num", &[]),
+    ("governance_votes", "block_number", &[]),
+    ("release_batch_queue_items", "block_number", &[]),
+    ("release_batch_events", "block_number", &[]),
+    ("article_vote_windows", "block_number", &[]),
+    ("article_votes", "block_number", &[]),
+    ("article_vote_results", "block_number", &[]),
+    ("press_events", "block_number", &["event"]),
+    ("court_cases", "block_number", &[]),
+    ("disputes", "block_number", &[]),
+    ("dispute_events", "block_number", &[]),
+    ("treasury_ledger", "block_number", &["leg"]),
+    ("council_bond_events", "block_number", &[]),
+    ("council_membership", "block_number", &[]),
+    ("council_actions", "block_number", &[]),
+    ("governance_signal_votes", "block_number", &[]),
+    ("arweave_import_jobs", "block_number", &[]),
+    ("arweave_imports", "block_number", &[]),
+    ("arweave_import_policy", "block_number", &[]),
+];
+
+// Columns that differ between two copies of the same legacy row.
+const VOLATILE: [&str; 5] = ["id", "inserted_at", "recorded_at", "created_at", "finalized"];
+
+pub fn key_cols(extra: &[&str]) -> String {
+    let mut cols = vec!["chain_id", "tx_hash", "log_index"];
+    cols.extend_from_slice(extra);
+    cols.join(", ")
+}
+
+async fn create_unique(db: &Db, table: &str, extra: &[&str]) -> Result<(), String> {
+    sqlx::query(&format!("CREATE UNIQUE INDEX IF NOT EXISTS uq_{t}_event ON {t}({k}) WHERE log_index >= 0", t = table, k = key_cols(extra)))
+        .execute(db).await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+// Stamps rows written without a known chain id and creates the unique indexes the upserts target.
+// The key columns come from the migrations; the indexes stay out of them because an adopted DB may
+// hold duplicates, which are removed here first (earliest copy kept).
+pub async fn ensure_event_keys(db: &Db) -> Result<(), String> {
+    for (table, block_col, extra) in EVENT_TABLES {
+        if !store::table_exists(db, table).await { continue; }
+        if chain_id() != 0 {
+            let _ = sqlx::query(&format!("UPDATE {} SET chain_id=$1 WHERE chain_id=0", table)).bind(chain_id()).execute(db).await;
+        }
+        if create_unique(db, table, extra).await.is_ok() { continue; }
+        let n = remove_duplicates(db, table, block_col, extra, false).await;
+        eprintln!("dedupe: {}: removed {} duplicate rows", table, n);
+        create_unique(db, table, extra).await
+            .map_err(|e| format!("{}: cannot create the unique index on ({}): {}", table, key_cols(extra), e))?;
+    }
+    Ok(())
+}
+
+// Rows that duplicate an earlier row: same key for keyed rows, same content for legacy rows. The
+// earliest copy (lowest row id) is kept so ids handed out as cursors stay valid.
+fn duplicates_sql(row_id: &str, table: &str, block_col: &str, extra: &[&str], cols: &[String]) -> String {
+    let content: Vec<&str> = cols.iter().map(|c| c.as_str()).filter(|c| !VOLATILE.contains(c)).collect();
+    format!(
+        "(log_index >= 0 AND {r} NOT IN (SELECT MIN({r}) FROM {t} WHERE log_index >= 0 GROUP BY {k})) \
+         OR (log_index < 0 AND {b} > 0 AND {r} NOT IN (SELECT MIN({r}) FROM {t} WHERE log_index < 0 AND {b} > 0 GROUP BY {c}))",
+        r = row_id, t = table, k = key_cols(extra), b = block_col, c = content.join(", "),
+    )
+}
+
+// Counts (dry run) or deletes the duplicate rows of one table.
+async fn remove_duplicates(db: &Db, table: &str, block_col: &str, extra: &[&str], dry_run: bool) -> i64 {
+    let cols: Vec<String> = store::columns(db, table).await.into_iter().map(|(c, _)| c).collect();
+    let cond = duplicates_sql(store::dialect(db).row_id(), table, block_col, extra, &cols);
+    if dry_run {
+        sqlx::query(&format!("SELECT COUNT(*) AS n FROM {} WHERE {}", table, cond))
+            .fetch_one(db).await.map(|r| r.get::<i64,_>("n")).unwrap_or(0)
+    } else {
+        match sqlx::query(&format!("DELETE FROM {} WHERE {}", table, cond)).execute(db).await {
+            Ok(r) => r.rows_affected() as i64,
+            Err(e) => { eprintln!("dedupe: {}: {}", table, e); 0 }
+        }
+    }
+}
+
+// Counts (dry run) or deletes duplicate rows per table, then retries the unique indexes.
+pub async fn dedupe(db: &Db, dry_run: bool) -> Vec<(String, i64)> {
+    let mut out = vec![];
+    for (table, block_col, extra) in EVENT_TABLES {
+        if !store::table_exists(db, table).await { continue; }
+        let n = remove_duplicates(db, table, block_col, extra, dry_run).await;
+        if !dry_run {
+            if let Err(e) = create_unique(db, table, extra).await { eprintln!("dedupe: {}: unique index still failing: {}", table, e); }
+        }
+        out.push((table.to_string(), n));
+    }
+    out
+}
+
+// press_indexer dedupe [--dry-run]
+pub async fn cli(db: &Db, args: &[String]) -> i32 {
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let mut total = 0;
+    for (table, n) in dedupe(db, dry_run).await {
+        if n > 0 { println!("{:<32} {}", table, n); }
+        total += n;
+    }
+    println!("{} duplicate rows {}", total, if dry_run { "found" } else { "removed" });
+    0
+}
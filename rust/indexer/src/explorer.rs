@@ -0,0 +1,224 @@
+// Decoded transactions.
+//
+// /explorer/tx/:hash fetches a receipt and decodes the call and every log with the ABIs in the ABI
+// dirs (/state/abi, contracts/abi). Contracts are named from the address book: /state/deploy.json
+// plus the /state/<name>_address.txt files written by the deploy script. A log from a contract
+// whose name matches an ABI file (outletRegistry / press_court ~ OutletRegistry.abi.json /
+// PressCourt.abi.json) is decoded with that ABI; any other log is matched by topic0 against every
+// ABI and reported with `"abi": "signature"`, since two contracts may share an event signature.
+// /explorer/block/:number does the same for the logs of known contracts in one block.
+
+use crate::recipes::{abi_dirs, token_json};
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::Json;
+use ethers::core::abi::{Abi, Event, RawLog};
+use ethers::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+struct Contracts {
+    // address -> contract name
+    book: HashMap<Address, String>,
+    // (normalized name, file stem, ABI) per ABI file
+    abis: Vec<(String, String, Abi)>,
+}
+
+// "outletRegistry", "press_court" and "PressCourt" all become "outletregistry" / "presscourt".
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase()
+}
+
+fn address_book() -> HashMap<Address, String> {
+    let mut book = HashMap::new();
+    if let Ok(entries) = std::fs::read_dir("/state") {
+        for e in entries.flatten() {
+            let file = e.file_name().to_string_lossy().to_string();
+            let Some(name) = file.strip_suffix("_address.txt") else { continue };
+            if let Some(a) = crate::read_state_string(&e.path().to_string_lossy()).and_then(|s| s.parse::<Address>().ok()) {
+                book.insert(a, name.to_string());
+            }
+        }
+    }
+    // deploy.json names win over the address files
+    let deploy: serde_json::Value = std::fs::read_to_string("/state/deploy.json").ok()
+        .and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+    if let Some(obj) = deploy.as_object() {
+        for (k, v) in obj {
+            if let Some(a) = v.as_str().and_then(|s| s.parse::<Address>().ok()).filter(|a| !a.is_zero()) {
+                book.insert(a, k.clone());
+            }
+        }
+    }
+    book
+}
+
+fn load_abis() -> Vec<(String, String, Abi)> {
+    let mut out: Vec<(String, String, Abi)> = vec![];
+    for dir in abi_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for e in entries.flatten() {
+            let file = e.file_name().to_string_lossy().to_string();
+            let Some(stem) = file.strip_suffix(".json") else { continue };
+            let stem = stem.strip_suffix(".abi").unwrap_or(stem).to_string();
+            // the first dir wins, so /state/abi overrides the bundled ABIs
+            if out.iter().any(|(_, s, _)| *s == stem) { continue; }
+            if let Some(abi) = crate::recipes::load_abi(&file) {
+                out.push((normalize(&stem), stem, abi));
+            }
+        }
+    }
+    out
+}
+
+impl Contracts {
+    fn load() -> Self {
+        Contracts { book: address_book(), abis: load_abis() }
+    }
+
+    fn name(&self, a: &Address) -> Option<&String> { self.book.get(a) }
+
+    fn abi_of(&self, a: &Address) -> Option<&(String, String, Abi)> {
+        let n = normalize(self.name(a)?);
+        self.abis.iter().find(|(k, _, _)| *k == n)
+    }
+
+    // (event, ABI file, matched by contract) for a log
+    fn event_for(&self, lg: &Log) -> Option<(&Event, &str, bool)> {
+        if let Some((_, stem, abi)) = self.abi_of(&lg.address) {
+            if let Some(e) = find_event(abi, lg) { return Some((e, stem, true)); }
+        }
+        self.abis.iter().find_map(|(_, stem, abi)| find_event(abi, lg).map(|e| (e, stem.as_str(), false)))
+    }
+}
+
+// Same topic0 and the same number of indexed params (uint256 vs indexed uint256 share a topic0).
+fn find_event<'a>(abi: &'a Abi, lg: &Log) -> Option<&'a Event> {
+    let t0 = *lg.topics.first()?;
+    abi.events().find(|e| !e.anonymous && e.signature() == t0 && e.inputs.iter().filter(|p| p.indexed).count() + 1 == lg.topics.len())
+}
+
+fn signature(name: &str, kinds: Vec<String>) -> String {
+    format!("{}({})", name, kinds.join(","))
+}
+
+fn log_json(c: &Contracts, lg: &Log) -> serde_json::Value {
+    let mut v = json!({
+        "log_index": lg.log_index.map(|i| i.as_u64()),
+        "address": format!("{:?}", lg.address),
+        "contract": c.name(&lg.address),
+    });
+    let decoded = c.event_for(lg).and_then(|(ev, stem, by_contract)| {
+        let parsed = ev.parse_log(RawLog { topics: lg.topics.clone(), data: lg.data.to_vec() }).ok()?;
+        let params: Vec<serde_json::Value> = ev.inputs.iter().zip(parsed.params.iter()).map(|(p, lp)| json!({
+            "name": lp.name,
+            "type": p.kind.to_string(),
+            "indexed": p.indexed,
+            "value": token_json(&lp.value),
+        })).collect();
+        Some(json!({
+            "event": ev.name,
+            "signature": signature(&ev.name, ev.inputs.iter().map(|p| p.kind.to_string()).collect()),
+            "abi_file": stem,
+            "abi": if by_contract { "contract" } else { "signature" },
+            "params": params,
+        }))
+    });
+    match decoded {
+        Some(d) => { v["decoded"] = json!(true); v["event"] = d; }
+        None => {
+            v["decoded"] = json!(false);
+            v["topics"] = json!(lg.topics.iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>());
+            v["data"] = json!(format!("0x{}", hex::encode(&lg.data)));
+        }
+    }
+    v
+}
+
+// The called function, when the target's ABI (or failing that, any ABI) has its selector.
+fn call_json(c: &Contracts, to: &Address, input: &[u8]) -> serde_json::Value {
+    if input.len() < 4 { return serde_json::Value::Null; }
+    let selector = &input[..4];
+    let own = c.abi_of(to).into_iter();
+    let found = own.chain(c.abis.iter()).find_map(|(_, stem, abi)| {
+        let f = abi.functions().find(|f| f.short_signature() == selector)?;
+        let toks = f.decode_input(&input[4..]).ok()?;
+        Some(json!({
+            "function": f.name,
+            "signature": signature(&f.name, f.inputs.iter().map(|p| p.kind.to_string()).collect()),
+            "abi_file": stem,
+            "params": f.inputs.iter().zip(toks.iter()).map(|(p, t)| json!({
+                "name": p.name,
+                "type": p.kind.to_string(),
+                "value": token_json(t),
+            })).collect::<Vec<_>>(),
+        }))
+    });
+    found.unwrap_or_else(|| json!({"selector": format!("0x{}", hex::encode(selector))}))
+}
+
+pub async fn tx(State(st): State<AppState>, Path(hash): Path<String>) -> Json<serde_json::Value> {
+    let Ok(h) = hash.parse::<H256>() else { return Json(json!({"ok": false, "error": "bad tx hash"})) };
+    let provider = match Provider::<Http>::try_from(st.rpc_http.clone()) {
+        Ok(p) => p,
+        Err(e) => return Json(json!({"ok": false, "error": e.to_string()})),
+    };
+    let receipt = match provider.get_transaction_receipt(h).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return Json(json!({"ok": false, "error": "no receipt (unknown or pending tx)"})),
+        Err(e) => return Json(json!({"ok": false, "error": e.to_string()})),
+    };
+    let c = Contracts::load();
+    let txn = provider.get_transaction(h).await.ok().flatten();
+    let to = receipt.to.or(receipt.contract_address);
+    let call = match (&txn, &receipt.to) {
+        (Some(t), Some(to)) => call_json(&c, to, &t.input),
+        _ => serde_json::Value::Null,
+    };
+    Json(json!({
+        "ok": true,
+        "tx": {
+            "hash": format!("{:?}", receipt.transaction_hash),
+            "block_number": receipt.block_number.map(|b| b.as_u64()),
+            "from": format!("{:?}", receipt.from),
+            "from_name": c.name(&receipt.from),
+            "to": to.map(|a| format!("{:?}", a)),
+            "to_name": to.as_ref().and_then(|a| c.name(a)),
+            "contract_created": receipt.contract_address.map(|a| format!("{:?}", a)),
+            "status": receipt.status.map(|s| s.as_u64()),
+            "gas_used": receipt.gas_used.map(|g| g.to_string()),
+            "value": txn.as_ref().map(|t| t.value.to_string()),
+            "call": call,
+        },
+        "logs": receipt.logs.iter().map(|lg| log_json(&c, lg)).collect::<Vec<_>>(),
+    }))
+}
+
+// Logs of address-book contracts in one block, grouped by transaction.
+pub async fn block(State(st): State<AppState>, Path(number): Path<u64>) -> Json<serde_json::Value> {
+    let provider = match Provider::<Http>::try_from(st.rpc_http.clone()) {
+        Ok(p) => p,
+        Err(e) => return Json(json!({"ok": false, "error": e.to_string()})),
+    };
+    let c = Contracts::load();
+    if c.book.is_empty() { return Json(json!({"ok": false, "error": "address book is empty (no /state/deploy.json)"})); }
+    let f = Filter::new().from_block(number).to_block(number).address(c.book.keys().cloned().collect::<Vec<_>>());
+    let logs = match provider.get_logs(&f).await {
+        Ok(l) => l,
+        Err(e) => return Json(json!({"ok": false, "error": e.to_string()})),
+    };
+    let mut txs: Vec<(H256, Vec<serde_json::Value>)> = vec![];
+    for lg in &logs {
+        let h = lg.transaction_hash.unwrap_or_default();
+        let j = log_json(&c, lg);
+        match txs.iter_mut().find(|(t, _)| *t == h) {
+            Some((_, v)) => v.push(j),
+            None => txs.push((h, vec![j])),
+        }
+    }
+    Json(json!({
+        "ok": true,
+        "block_number": number,
+        "transactions": txs.into_iter().map(|(h, logs)| json!({"hash": format!("{:?}", h), "logs": logs})).collect::<Vec<_>>(),
+    }))
+}
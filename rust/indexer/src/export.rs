@@ -0,0 +1,570 @@
+// Bulk export.
+//
+// Any indexed table (or a feed, by the table behind it) can be exported for a block or time range
+// as CSV, NDJSON or Parquet, with column selection and equality filters. `GET /export` and
+// `press_indexer export` take the same parameters. CSV and NDJSON are streamed from the DB as rows
+// arrive; Parquet is built in memory, in row groups, because its footer has to come last.
+//
+// With INDEXER_EXPORT_INTERVAL_SECS set, the tables in INDEXER_EXPORT_TABLES are also snapshotted
+// into /state/exports. Snapshots of stream tables are incremental: each file covers the blocks
+// finalized since the previous one, so a file never changes once written. Other tables are written
+// whole every time. Every file is listed in /state/exports/manifest.json with its range, row count
+// and sha256.
+
+use crate::store::{self, Db, DbRow};
+use crate::{checkpoints, now_iso, recipes, stream_tables, AppState, STREAMS};
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures_util::StreamExt;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+const EXPORT_DIR: &str = "/state/exports";
+const PARQUET_ROW_GROUP: usize = 50_000;
+const CHUNK_BYTES: usize = 64 * 1024;
+
+// Tables outside the streams that are still worth exporting.
+const OTHER_TABLES: [&str; 5] = ["exchange_listings", "release_batches", "oracle_flags", "outlet_domain_checks", "token_tests"];
+
+// feed name -> table it is read from (pending_votes is derived: press_events with event=VotingStarted).
+// Recipe tables all start with recipe_, so a feed name never shadows one.
+const FEEDS: [(&str, &str); 3] = [("recent_articles", "press_events"), ("proposals", "governance_proposals"), ("oracle_flags", "oracle_flags")];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format { Csv, Ndjson, Parquet }
+
+impl Format {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "csv" => Ok(Format::Csv),
+            "ndjson" | "jsonl" => Ok(Format::Ndjson),
+            "parquet" => Ok(Format::Parquet),
+            other => Err(format!("unknown format {} (csv, ndjson, parquet)", other)),
+        }
+    }
+
+    fn ext(self) -> &'static str {
+        match self { Format::Csv => "csv", Format::Ndjson => "ndjson", Format::Parquet => "parquet" }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self { Format::Csv => "text/csv; charset=utf-8", Format::Ndjson => "application/x-ndjson", Format::Parquet => "application/vnd.apache.parquet" }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct ExportReq {
+    pub table: String,
+    pub format: String,
+    pub columns: Vec<String>,
+    pub from_block: Option<i64>,
+    pub to_block: Option<i64>,
+    // unix seconds
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<i64>,
+    // column = value
+    pub filters: Vec<(String, String)>,
+}
+
+impl ExportReq {
+    // ?table=&format=&columns=a,b&from_block=&to_block=&since=&until=&limit=&filter.<column>=<value>
+    fn from_query(q: &HashMap<String, String>) -> Self {
+        let num = |k: &str| q.get(k).and_then(|v| v.parse::<i64>().ok());
+        ExportReq {
+            table: q.get("table").cloned().unwrap_or_default(),
+            format: q.get("format").cloned().unwrap_or_default(),
+            columns: split_list(q.get("columns").map(|s| s.as_str()).unwrap_or("")),
+            from_block: num("from_block"),
+            to_block: num("to_block"),
+            since: num("since"),
+            until: num("until"),
+            limit: num("limit"),
+            filters: q.iter().filter_map(|(k, v)| k.strip_prefix("filter.").map(|c| (c.to_string(), v.clone()))).collect(),
+        }
+    }
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect()
+}
+
+#[derive(Clone, Copy)]
+enum Kind { Int, Float, Bool, Text }
+
+fn kind_of(sql_type: &str) -> Kind {
+    let t = sql_type.to_ascii_lowercase();
+    if t.contains("int") { Kind::Int }
+    else if t.contains("real") || t.contains("double") || t.contains("float") || t.contains("numeric") { Kind::Float }
+    else if t.contains("bool") { Kind::Bool }
+    else { Kind::Text }
+}
+
+#[derive(Serialize, Clone)]
+pub struct Exportable {
+    pub table: String,
+    pub stream: Option<String>,
+    pub block_column: Option<String>,
+}
+
+// Stream tables (with the column their block range applies to), recipe tables, then the rest.
+pub fn exportable(st: &AppState) -> Vec<Exportable> {
+    let mut out = vec![];
+    for stream in STREAMS {
+        for (t, c) in stream_tables(stream) {
+            out.push(Exportable { table: t.to_string(), stream: Some(stream.to_string()), block_column: Some(c.to_string()) });
+        }
+    }
+    for r in st.recipes.iter() {
+        for (t, c) in recipes::recipe_tables(r) {
+            out.push(Exportable { table: t.to_string(), stream: Some(format!("recipe:{}", r.name)), block_column: Some(c.to_string()) });
+        }
+    }
+    for t in OTHER_TABLES {
+        out.push(Exportable { table: t.to_string(), stream: None, block_column: None });
+    }
+    out
+}
+
+enum Bind { Int(i64), Float(f64), Bool(bool), Text(String) }
+
+pub struct Plan {
+    pub table: String,
+    pub format: Format,
+    cols: Vec<(String, Kind)>,
+    sql: String,
+    binds: Vec<Bind>,
+}
+
+// Unix-second time column, or an RFC 3339 text one (now_iso) compared as strings.
+fn time_column(table: &str, cols: &[(String, String)]) -> Option<(String, bool)> {
+    let has = |c: &str| cols.iter().any(|(n, _)| n == c);
+    if has("ts") { return Some(("ts".into(), true)); }
+    if has("block_time") { return Some(("block_time".into(), true)); }
+    // proposal timestamps come from the chain as decimal strings
+    if table == "governance_proposals" { return Some(("CAST(created_at AS BIGINT)".into(), true)); }
+    ["inserted_at", "recorded_at", "created_at", "checked_at", "tested_at", "updated_at"].iter()
+        .find(|c| has(c)).map(|c| (c.to_string(), false))
+}
+
+fn iso(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0).map(|t| t.to_rfc3339()).unwrap_or_default()
+}
+
+pub async fn plan(st: &AppState, req: &ExportReq) -> Result<Plan, String> {
+    let format = Format::parse(&req.format)?;
+    let name = FEEDS.iter().find(|(f, _)| *f == req.table).map(|(_, t)| t.to_string()).unwrap_or_else(|| req.table.clone());
+    let Some(target) = exportable(st).into_iter().find(|e| e.table == name) else {
+        return Err(format!("unknown table {}", req.table));
+    };
+    let all = store::columns(&st.db, &target.table).await;
+    if all.is_empty() { return Err(format!("table {} does not exist yet", target.table)); }
+    let kind = |c: &str| all.iter().find(|(n, _)| n == c).map(|(_, t)| kind_of(t));
+
+    let mut cols = vec![];
+    let wanted: Vec<String> = if req.columns.is_empty() { all.iter().map(|(n, _)| n.clone()).collect() } else { req.columns.clone() };
+    for c in wanted {
+        let k = kind(&c).ok_or_else(|| format!("{} has no column {}", target.table, c))?;
+        cols.push((c, k));
+    }
+
+    let mut conds: Vec<String> = vec![];
+    let mut binds: Vec<Bind> = vec![];
+    let mut push = |cond: String, b: Bind, binds: &mut Vec<Bind>| {
+        binds.push(b);
+        conds.push(cond.replace("$?", &format!("${}", binds.len())));
+    };
+    if req.from_block.is_some() || req.to_block.is_some() {
+        let bc = target.block_column.as_deref().ok_or_else(|| format!("{} has no block column; use since/until", target.table))?;
+        if let Some(n) = req.from_block { push(format!("{} >= $?", bc), Bind::Int(n), &mut binds); }
+        if let Some(n) = req.to_block { push(format!("{} <= $?", bc), Bind::Int(n), &mut binds); }
+    }
+    if req.since.is_some() || req.until.is_some() {
+        let (tc, unix) = time_column(&target.table, &all).ok_or_else(|| format!("{} has no time column", target.table))?;
+        let b = |secs: i64| if unix { Bind::Int(secs) } else { Bind::Text(iso(secs)) };
+        if let Some(n) = req.since { push(format!("{} >= $?", tc), b(n), &mut binds); }
+        if let Some(n) = req.until { push(format!("{} <= $?", tc), b(n), &mut binds); }
+    }
+    for (c, v) in &req.filters {
+        let k = kind(c).ok_or_else(|| format!("{} has no column {}", target.table, c))?;
+        let b = match k {
+            Kind::Int => Bind::Int(v.parse().map_err(|_| format!("filter.{}: {} is not an integer", c, v))?),
+            Kind::Float => Bind::Float(v.parse().map_err(|_| format!("filter.{}: {} is not a number", c, v))?),
+            Kind::Bool => Bind::Bool(matches!(v.as_str(), "1" | "true")),
+            Kind::Text => Bind::Text(v.clone()),
+        };
+        push(format!("{} = $?", c), b, &mut binds);
+    }
+
+    let has = |c: &str| all.iter().any(|(n, _)| n == c);
+    let order = match (&target.block_column, has("log_index")) {
+        (Some(bc), true) => format!(" ORDER BY {}, log_index", bc),
+        (Some(bc), false) => format!(" ORDER BY {}", bc),
+        (None, _) if has("id") => " ORDER BY id".to_string(),
+        (None, _) => String::new(),
+    };
+    let mut sql = format!("SELECT {} FROM {}", cols.iter().map(|(c, _)| c.as_str()).collect::<Vec<_>>().join(", "), target.table);
+    if !conds.is_empty() { sql.push_str(&format!(" WHERE {}", conds.join(" AND "))); }
+    sql.push_str(&order);
+    if let Some(n) = req.limit.filter(|n| *n > 0) {
+        binds.push(Bind::Int(n));
+        sql.push_str(&format!(" LIMIT ${}", binds.len()));
+    }
+    Ok(Plan { table: target.table, format, cols, sql, binds })
+}
+
+fn cell(r: &DbRow, i: usize) -> serde_json::Value {
+    if let Ok(v) = r.try_get::<Option<i64>, _>(i) { return json!(v); }
+    if let Ok(v) = r.try_get::<Option<f64>, _>(i) { return json!(v); }
+    if let Ok(v) = r.try_get::<Option<bool>, _>(i) { return json!(v); }
+    if let Ok(v) = r.try_get::<Option<String>, _>(i) { return json!(v); }
+    serde_json::Value::Null
+}
+
+fn csv_field(v: &serde_json::Value) -> String {
+    let s = match v {
+        serde_json::Value::Null => return String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if s.contains([',', '"', '\n', '\r']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s }
+}
+
+pub enum Out {
+    Chan(mpsc::Sender<Result<Vec<u8>, std::io::Error>>),
+    Writer(Box<dyn Write + Send>),
+}
+
+impl Out {
+    async fn put(&mut self, bytes: Vec<u8>) -> Result<(), String> {
+        match self {
+            Out::Chan(tx) => tx.send(Ok(bytes)).await.map_err(|_| "client went away".to_string()),
+            Out::Writer(w) => w.write_all(&bytes).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+// Runs the plan and writes every row to `out`; returns the number of rows.
+pub async fn write(db: &Db, plan: &Plan, out: &mut Out) -> Result<u64, String> {
+    let mut q = sqlx::query(&plan.sql);
+    for b in &plan.binds {
+        q = match b { Bind::Int(n) => q.bind(*n), Bind::Float(f) => q.bind(*f), Bind::Bool(v) => q.bind(*v), Bind::Text(s) => q.bind(s.clone()) };
+    }
+    let mut rows = q.fetch(db);
+    let mut n = 0u64;
+    if plan.format == Format::Parquet {
+        let mut buf = vec![];
+        let mut pq = ParquetOut::new(&mut buf, &plan.cols)?;
+        let mut group = vec![];
+        while let Some(r) = rows.next().await {
+            let r = r.map_err(|e| e.to_string())?;
+            group.push((0..plan.cols.len()).map(|i| cell(&r, i)).collect::<Vec<_>>());
+            n += 1;
+            if group.len() >= PARQUET_ROW_GROUP { pq.row_group(&group)?; group.clear(); }
+        }
+        if !group.is_empty() || n == 0 { pq.row_group(&group)?; }
+        pq.close()?;
+        out.put(buf).await?;
+        return Ok(n);
+    }
+
+    let mut buf = vec![];
+    if plan.format == Format::Csv {
+        let header: Vec<&str> = plan.cols.iter().map(|(c, _)| c.as_str()).collect();
+        buf.extend_from_slice(format!("{}\n", header.join(",")).as_bytes());
+    }
+    while let Some(r) = rows.next().await {
+        let r = r.map_err(|e| e.to_string())?;
+        let vals: Vec<serde_json::Value> = (0..plan.cols.len()).map(|i| cell(&r, i)).collect();
+        match plan.format {
+            Format::Csv => {
+                let line: Vec<String> = vals.iter().map(csv_field).collect();
+                buf.extend_from_slice(line.join(",").as_bytes());
+            }
+            _ => {
+                let obj: serde_json::Map<String, serde_json::Value> = plan.cols.iter().map(|(c, _)| c.clone()).zip(vals).collect();
+                buf.extend_from_slice(serde_json::Value::Object(obj).to_string().as_bytes());
+            }
+        }
+        buf.push(b'\n');
+        n += 1;
+        if buf.len() >= CHUNK_BYTES { out.put(std::mem::take(&mut buf)).await?; }
+    }
+    if !buf.is_empty() { out.put(buf).await?; }
+    Ok(n)
+}
+
+// --- Parquet ---
+// Every column is OPTIONAL; integers are INT64, reals DOUBLE, booleans BOOLEAN and the rest UTF8.
+
+struct ParquetOut<'a> {
+    w: SerializedFileWriter<&'a mut Vec<u8>>,
+    kinds: Vec<Kind>,
+}
+
+impl<'a> ParquetOut<'a> {
+    fn new(buf: &'a mut Vec<u8>, cols: &[(String, Kind)]) -> Result<Self, String> {
+        let fields: Vec<String> = cols.iter().map(|(c, k)| match k {
+            Kind::Int => format!("OPTIONAL INT64 {};", c),
+            Kind::Float => format!("OPTIONAL DOUBLE {};", c),
+            Kind::Bool => format!("OPTIONAL BOOLEAN {};", c),
+            Kind::Text => format!("OPTIONAL BYTE_ARRAY {} (UTF8);", c),
+        }).collect();
+        let schema = parse_message_type(&format!("message export {{ {} }}", fields.join(" "))).map_err(|e| e.to_string())?;
+        let props = WriterProperties::builder().build();
+        let w = SerializedFileWriter::new(buf, Arc::new(schema), Arc::new(props)).map_err(|e| e.to_string())?;
+        Ok(ParquetOut { w, kinds: cols.iter().map(|(_, k)| *k).collect() })
+    }
+
+    fn row_group(&mut self, rows: &[Vec<serde_json::Value>]) -> Result<(), String> {
+        let err = |e: parquet::errors::ParquetError| e.to_string();
+        let mut rg = self.w.next_row_group().map_err(err)?;
+        let mut i = 0;
+        while let Some(mut col) = rg.next_column().map_err(err)? {
+            let vals = rows.iter().map(|r| &r[i]);
+            match self.kinds[i] {
+                Kind::Int => {
+                    let v: Vec<Option<i64>> = vals.map(|v| v.as_i64().or_else(|| v.as_bool().map(|b| b as i64)).or_else(|| v.as_str().and_then(|s| s.parse().ok()))).collect();
+                    let def: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                    let v: Vec<i64> = v.into_iter().flatten().collect();
+                    col.typed::<Int64Type>().write_batch(&v, Some(&def), None).map_err(err)?;
+                }
+                Kind::Float => {
+                    let v: Vec<Option<f64>> = vals.map(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))).collect();
+                    let def: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                    let v: Vec<f64> = v.into_iter().flatten().collect();
+                    col.typed::<DoubleType>().write_batch(&v, Some(&def), None).map_err(err)?;
+                }
+                Kind::Bool => {
+                    let v: Vec<Option<bool>> = vals.map(|v| v.as_bool().or_else(|| v.as_i64().map(|n| n != 0))).collect();
+                    let def: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                    let v: Vec<bool> = v.into_iter().flatten().collect();
+                    col.typed::<BoolType>().write_batch(&v, Some(&def), None).map_err(err)?;
+                }
+                Kind::Text => {
+                    let v: Vec<Option<ByteArray>> = vals.map(|v| match v {
+                        serde_json::Value::Null => None,
+                        serde_json::Value::String(s) => Some(ByteArray::from(s.as_str())),
+                        other => Some(ByteArray::from(other.to_string().as_str())),
+                    }).collect();
+                    let def: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                    let v: Vec<ByteArray> = v.into_iter().flatten().collect();
+                    col.typed::<ByteArrayType>().write_batch(&v, Some(&def), None).map_err(err)?;
+                }
+            }
+            col.close().map_err(err)?;
+            i += 1;
+        }
+        rg.close().map_err(err)?;
+        Ok(())
+    }
+
+    fn close(self) -> Result<(), String> {
+        self.w.close().map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+// --- HTTP ---
+
+fn bad_request(e: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({"ok": false, "error": e}))).into_response()
+}
+
+pub async fn http(State(st): State<AppState>, Query(q): Query<HashMap<String, String>>) -> Response {
+    let plan = match plan(&st, &ExportReq::from_query(&q)).await {
+        Ok(p) => p,
+        Err(e) => return bad_request(e),
+    };
+    let (tx, rx) = mpsc::channel(8);
+    let filename = format!("{}.{}", plan.table, plan.format.ext());
+    let content_type = plan.format.content_type();
+    let db = st.db.clone();
+    tokio::spawn(async move {
+        let mut out = Out::Chan(tx.clone());
+        if let Err(e) = write(&db, &plan, &mut out).await {
+            eprintln!("export {}: {}", plan.table, e);
+            let _ = tx.send(Err(std::io::Error::other(e))).await;
+        }
+    });
+    let body = Body::from_stream(futures_util::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|c| (c, rx)) }));
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    ).into_response()
+}
+
+pub async fn tables(State(st): State<AppState>) -> Json<serde_json::Value> {
+    let feeds: serde_json::Map<String, serde_json::Value> = FEEDS.iter().map(|(f, t)| (f.to_string(), json!(t))).collect();
+    Json(json!({"ok": true, "tables": exportable(&st), "feeds": feeds}))
+}
+
+pub async fn manifest_http() -> Json<serde_json::Value> {
+    Json(json!({"ok": true, "manifest": read_manifest()}))
+}
+
+// --- CLI ---
+
+// press_indexer export --table T [--format csv|ndjson|parquet] [--columns a,b] [--from-block N]
+//   [--to-block N] [--since UNIX] [--until UNIX] [--filter col=value]... [--limit N] [--out FILE]
+pub async fn cli(st: &AppState, args: &[String]) -> i32 {
+    let mut req = ExportReq::default();
+    let mut out_path: Option<String> = None;
+    let mut it = args.iter();
+    while let Some(flag) = it.next() {
+        let val = it.next().cloned().unwrap_or_default();
+        match flag.as_str() {
+            "--table" => req.table = val,
+            "--format" => req.format = val,
+            "--columns" => req.columns = split_list(&val),
+            "--from-block" => req.from_block = val.parse().ok(),
+            "--to-block" => req.to_block = val.parse().ok(),
+            "--since" => req.since = val.parse().ok(),
+            "--until" => req.until = val.parse().ok(),
+            "--limit" => req.limit = val.parse().ok(),
+            "--filter" => match val.split_once('=') {
+                Some((c, v)) => req.filters.push((c.to_string(), v.to_string())),
+                None => { eprintln!("--filter takes column=value"); return 2; }
+            },
+            "--out" => out_path = Some(val),
+            other => { eprintln!("unknown flag {other}"); return 2; }
+        }
+    }
+    let plan = match plan(st, &req).await {
+        Ok(p) => p,
+        Err(e) => { eprintln!("export: {e}"); return 1; }
+    };
+    let writer: Box<dyn Write + Send> = match &out_path {
+        Some(p) => match std::fs::File::create(p) {
+            Ok(f) => Box::new(std::io::BufWriter::new(f)),
+            Err(e) => { eprintln!("export: {}: {}", p, e); return 1; }
+        },
+        None => Box::new(std::io::stdout()),
+    };
+    let mut out = Out::Writer(writer);
+    match write(&st.db, &plan, &mut out).await {
+        Ok(n) => {
+            if let Some(p) = out_path { eprintln!("export: {} rows of {} to {}", n, plan.table, p); }
+            0
+        }
+        Err(e) => { eprintln!("export: {e}"); 1 }
+    }
+}
+
+// --- Scheduled snapshots ---
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ManifestEntry {
+    pub table: String,
+    pub format: String,
+    // relative to /state/exports
+    pub file: String,
+    pub from_block: Option<i64>,
+    pub to_block: Option<i64>,
+    pub rows: u64,
+    pub bytes: u64,
+    pub sha256: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Manifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+fn manifest_path() -> String { format!("{}/manifest.json", EXPORT_DIR) }
+
+pub fn read_manifest() -> Manifest {
+    std::fs::read_to_string(manifest_path()).ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(m: &Manifest) -> Result<(), String> {
+    let tmp = format!("{}.tmp", manifest_path());
+    std::fs::write(&tmp, serde_json::to_vec_pretty(m).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, manifest_path()).map_err(|e| e.to_string())
+}
+
+// INDEXER_EXPORT_TABLES: comma-separated `table` or `table:format` (default parquet)
+fn schedule() -> Vec<(String, String)> {
+    split_list(&std::env::var("INDEXER_EXPORT_TABLES").unwrap_or_default()).into_iter()
+        .map(|e| match e.split_once(':') {
+            Some((t, f)) => (t.to_string(), f.to_string()),
+            None => (e, "parquet".to_string()),
+        })
+        .collect()
+}
+
+async fn snapshot(st: &AppState, manifest: &mut Manifest, table: &str, format: &str) -> Result<(), String> {
+    let fmt = Format::parse(format)?;
+    let target = exportable(st).into_iter().find(|e| e.table == table).ok_or_else(|| format!("unknown table {}", table))?;
+    let mut req = ExportReq { table: table.to_string(), format: format.to_string(), ..Default::default() };
+    let stem = match &target.stream {
+        Some(stream) => {
+            // only finalized blocks, so a written file is never out of date
+            let fin = checkpoints::finalized(&st.db, stream).await;
+            let from = manifest.files.iter().rev()
+                .find(|e| e.table == table && e.format == fmt.ext())
+                .and_then(|e| e.to_block).map(|b| b + 1).unwrap_or(0);
+            if fin == 0 || fin < from { return Ok(()); }
+            req.from_block = Some(from);
+            req.to_block = Some(fin);
+            format!("{}-{:012}-{:012}", table, from, fin)
+        }
+        None => format!("{}-{}", table, chrono::Utc::now().format("%Y%m%dT%H%M%SZ")),
+    };
+    let plan = plan(st, &req).await?;
+    let dir = format!("{}/{}", EXPORT_DIR, table);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let rel = format!("{}/{}.{}", table, stem, fmt.ext());
+    let path = format!("{}/{}", EXPORT_DIR, rel);
+    let tmp = format!("{}.tmp", path);
+    let file = std::fs::File::create(&tmp).map_err(|e| e.to_string())?;
+    let rows = write(&st.db, &plan, &mut Out::Writer(Box::new(std::io::BufWriter::new(file)))).await?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+    let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+    manifest.files.push(ManifestEntry {
+        table: table.to_string(),
+        format: fmt.ext().to_string(),
+        file: rel,
+        from_block: req.from_block,
+        to_block: req.to_block,
+        rows,
+        bytes: data.len() as u64,
+        sha256: hex::encode(Sha256::digest(&data)),
+        created_at: now_iso(),
+    });
+    write_manifest(manifest)
+}
+
+pub async fn snapshot_loop(st: AppState) {
+    let secs = std::env::var("INDEXER_EXPORT_INTERVAL_SECS").ok().and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+    let tables = schedule();
+    if secs == 0 || tables.is_empty() { return; }
+    loop {
+        let _ = std::fs::create_dir_all(EXPORT_DIR);
+        let mut manifest = read_manifest();
+        for (table, format) in &tables {
+            if let Err(e) = snapshot(&st, &mut manifest, table, format).await {
+                eprintln!("export snapshot {}: {}", table, e);
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(secs)).await;
+    }
+}
@@ -0,0 +1,206 @@
+// Adaptive eth_getLogs windows.
+//
+// Streams read [cursor, cursor + window) instead of [cursor, head]. When the provider rejects a
+// request because the range or the result set is too large the window is halved and the same start
+// block is retried at once; every success doubles it again up to the configured maximum. Other
+// errors back off exponentially. Each stream's head, cursor, lag and error counters are kept in
+// memory and served from /ingest/stats; the last error of a live stream is also written to its
+// checkpoint. The same numbers go to the shared Prometheus registry (blocks read, head, cursor and
+// lag per stream, errors per stream and kind).
+
+use crate::{checkpoints, now_iso, AppState};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Serialize, Clone, Default)]
+pub struct StreamStats {
+    pub head: i64,
+    pub cursor: i64,
+    pub lag: i64,
+    pub window: u64,
+    pub requests: u64,
+    pub errors: u64,
+    pub limit_errors: u64,
+    pub consecutive_errors: u32,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+    pub last_success_at: Option<String>,
+}
+
+pub type Stats = Arc<std::sync::Mutex<BTreeMap<String, StreamStats>>>;
+
+fn env_u64(k: &str, d: u64) -> u64 {
+    std::env::var(k).ok().and_then(|s| s.parse::<u64>().ok()).filter(|n| *n > 0).unwrap_or(d)
+}
+
+// Provider rejections of a range or result set that is too large: JSON-RPC -32005 (limit exceeded),
+// -32602 (invalid params) when it says the range or result count is more than allowed, and the
+// exact messages of geth/infura, erigon, alchemy and quicknode style endpoints. Anything else, rate
+// limits included, takes the normal backoff path.
+pub fn is_limit_error(msg: &str) -> bool {
+    let m = msg.to_ascii_lowercase();
+    match rpc_code(&m) {
+        Some(-32005) => return true,
+        Some(-32602) if m.contains("more than") => return true,
+        _ => {}
+    }
+    [
+        "query returned more than",
+        "log response size exceeded",
+        "exceed maximum block range",
+        "query exceeds max block range",
+        "block range is too large",
+        "block range too large",
+        "eth_getlogs is limited to",
+        "query timeout exceeded",
+    ].iter().any(|p| m.contains(p))
+}
+
+// The error code of an ethers JsonRpcError ("(code: -32005, message: ...)") or a raw JSON-RPC
+// error object ("\"code\":-32005").
+fn rpc_code(m: &str) -> Option<i64> {
+    let rest = &m[m.find("code")? + 4..];
+    let rest = rest.trim_start_matches(['"', ':', ' ']);
+    let end = rest.char_indices().find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-'))).map_or(rest.len(), |(i, _)| i);
+    rest[..end].parse().ok()
+}
+
+pub struct Window {
+    stream: String,
+    size: u64,
+    max: u64,
+    failures: u32,
+    // start of the request made since the last `end`, for the blocks-processed counter
+    from: u64,
+    // live streams record errors in stream_checkpoints; backfill windows do not
+    checkpoint: bool,
+}
+
+impl Window {
+    pub fn new(stream: &str) -> Self {
+        let max = env_u64("INDEXER_LOGS_WINDOW_MAX", 10_000);
+        Window { stream: stream.to_string(), size: env_u64("INDEXER_LOGS_WINDOW", 2000).min(max), max, failures: 0, from: 0, checkpoint: true }
+    }
+
+    pub fn with_size(stream: &str, size: u64) -> Self {
+        Window { stream: stream.to_string(), size: size.max(1), max: size.max(1), failures: 0, from: 0, checkpoint: false }
+    }
+
+    pub fn size(&self) -> u64 { self.size }
+
+    // Last block of the next request starting at `from`.
+    pub fn end(&mut self, from: u64, head: u64) -> u64 {
+        self.from = from;
+        (from + self.size - 1).min(head)
+    }
+
+    pub fn ok(&mut self, st: &AppState, head: i64, cursor: i64) {
+        self.failures = 0;
+        press_metrics::indexer_blocks(&self.stream, (cursor + 1 - self.from as i64).max(0) as u64);
+        press_metrics::indexer_position(&self.stream, head, cursor);
+        self.size = (self.size * 2).min(self.max);
+        let mut stats = st.ingest.lock().unwrap();
+        let s = stats.entry(self.stream.clone()).or_default();
+        s.head = head;
+        s.cursor = cursor;
+        s.lag = (head - cursor).max(0);
+        s.window = self.size;
+        s.requests += 1;
+        s.consecutive_errors = 0;
+        s.last_success_at = Some(now_iso());
+    }
+
+    // Records the failure and waits as appropriate. Returns true when the window was shrunk, i.e. the
+    // caller should retry the same start block straight away.
+    pub async fn err(&mut self, st: &AppState, head: i64, cursor: i64, err: &str) -> bool {
+        let limit = is_limit_error(err) && self.size > 1;
+        if limit {
+            self.size = (self.size / 2).max(1);
+        } else {
+            self.failures = self.failures.saturating_add(1);
+        }
+        press_metrics::indexer_error(&self.stream, if limit { "limit" } else { "rpc" });
+        {
+            let mut stats = st.ingest.lock().unwrap();
+            let s = stats.entry(self.stream.clone()).or_default();
+            s.head = head;
+            s.cursor = cursor;
+            s.lag = (head - cursor).max(0);
+            s.window = self.size;
+            s.requests += 1;
+            s.errors += 1;
+            if limit { s.limit_errors += 1; }
+            s.consecutive_errors += 1;
+            s.last_error = Some(err.to_string());
+            s.last_error_at = Some(now_iso());
+        }
+        if self.checkpoint { checkpoints::record_error(&st.db, &self.stream, err).await; }
+        if limit {
+            eprintln!("{}: provider limit hit, window now {} blocks", self.stream, self.size);
+            return true;
+        }
+        let secs = (3u64 << self.failures.min(5)).min(120);
+        eprintln!("{}: get_logs error (retry in {}s): {}", self.stream, secs, err);
+        tokio::time::sleep(Duration::from_secs(secs)).await;
+        false
+    }
+
+    // For loops that fail before a request is made (head lookups); counted but no window change.
+    pub async fn fail(&mut self, st: &AppState, err: &str) {
+        self.failures = self.failures.saturating_add(1);
+        press_metrics::indexer_error(&self.stream, "head");
+        {
+            let mut stats = st.ingest.lock().unwrap();
+            let s = stats.entry(self.stream.clone()).or_default();
+            s.errors += 1;
+            s.consecutive_errors += 1;
+            s.last_error = Some(err.to_string());
+            s.last_error_at = Some(now_iso());
+        }
+        if self.checkpoint { checkpoints::record_error(&st.db, &self.stream, err).await; }
+        let secs = (3u64 << self.failures.min(5)).min(120);
+        tokio::time::sleep(Duration::from_secs(secs)).await;
+    }
+}
+
+pub fn snapshot(st: &AppState) -> BTreeMap<String, StreamStats> {
+    st.ingest.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_errors_by_code() {
+        assert!(is_limit_error("(code: -32005, message: query returned more than 10000 results, data: None)"));
+        assert!(is_limit_error("JSON-RPC error: (code: -32005, message: limit exceeded, data: None)"));
+        assert!(is_limit_error(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"limit exceeded"}}"#));
+        assert!(is_limit_error("(code: -32602, message: Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range, data: None)"));
+        assert!(is_limit_error("(code: -32602, message: query would return more than 10000 logs, data: None)"));
+    }
+
+    #[test]
+    fn limit_errors_by_provider_message() {
+        assert!(is_limit_error("query returned more than 10000 results"));
+        assert!(is_limit_error("Log response size exceeded. Try with this block range [0x1, 0x2]."));
+        assert!(is_limit_error("You can make eth_getLogs requests with up to a 2K block range. Exceed maximum block range: 2000"));
+        assert!(is_limit_error("query exceeds max block range 1000000"));
+        assert!(is_limit_error("block range is too large"));
+        assert!(is_limit_error("eth_getLogs is limited to a 10,000 range"));
+        assert!(is_limit_error("query timeout exceeded"));
+    }
+
+    #[test]
+    fn other_errors_are_not_limit_errors() {
+        assert!(!is_limit_error("(code: -32602, message: invalid argument 0: hex string has length 3, want 40 for common.Address, data: None)"));
+        assert!(!is_limit_error("(code: -32000, message: header not found, data: None)"));
+        assert!(!is_limit_error("HTTP status client error (429 Too Many Requests): rate limit exceeded"));
+        assert!(!is_limit_error("invalid block range params"));
+        assert!(!is_limit_error("error decoding response body: response size unknown"));
+        assert!(!is_limit_error("error sending request: connection refused"));
+        assert!(!is_limit_error("(code: -32603, message: internal error, data: None)"));
+    }
+}
@@ -0,0 +1,437 @@
+// Governance votes and proposal progress.
+//
+// PressGovernance emits VoteCast(id, voter, support, weight, feePaid) once per voter and proposal
+// (vote() rejects a second vote), stored in governance_votes. Tallies are summed from those rows on
+// read, so a reorg rollback of the governance stream also rolls back the tallies; ProposalFinalized
+// reports the contract's own yes/no totals, which are kept in approved_updates next to them.
+// There is no CouncilVote event in any contract: council-side votes are PressGovernanceSignals'
+// ProposalVoted, which the council stream stores as council_actions and in governance_signal_votes.
+// Signals carry no weight, so each one counts as a single vote; a wallet that voted through both
+// contracts is counted once, by its VoteCast.
+//
+// Thresholds are read from the contract: thresholdByKey(configKey) when that key has one set, else
+// defaultThreshold(). thresholdByAction is not used because ProposalCreated does not carry the
+// action type. finalize() itself only requires yes > no; the thresholds are what the council
+// executor is expected to check before finalizing, so the endpoint reports how far a proposal is
+// from each of them.
+
+use crate::store;
+use crate::{add_units, dedupe, log_index_of, log_tx_hash, now_iso, read_state_string, AppState, LimitQ, UnitsQ};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use ethers::core::abi::ParamType;
+use ethers::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+
+// VoteCast.feePaid and ProposalCreated.feePaid are msg.value
+const NATIVE_DECIMALS: u32 = 18;
+
+pub async fn handle_vote_cast(st: &AppState, lg: &Log) -> Result<(), String> {
+    // topics: [sig, id, voter]; data: support(bool), weight(uint256), feePaid(uint256)
+    if lg.topics.len() < 3 { return Ok(()); }
+    let proposal_id = U256::from_big_endian(lg.topics[1].as_bytes()).low_u64() as i64;
+    let voter = Address::from_slice(&lg.topics[2].as_bytes()[12..]);
+    let toks = ethers::core::abi::decode(&[ParamType::Bool, ParamType::Uint(256), ParamType::Uint(256)], &lg.data).map_err(|e| e.to_string())?;
+    let support = toks[0].clone().into_bool().unwrap_or(false);
+    let weight = toks[1].clone().into_uint().unwrap_or_default().to_string();
+    let fee = toks[2].clone().into_uint().unwrap_or_default().to_string();
+    let bn = lg.block_number.map(|b| b.as_u64() as i64).unwrap_or(0);
+    sqlx::query(&store::event_upsert(&st.db, "governance_votes", &["proposal_id", "voter", "support", "weight", "fee_paid", "block_number", "tx_hash", "inserted_at", "chain_id", "log_index"]))
+        .bind(proposal_id).bind(format!("{:?}", voter)).bind(if support {1i64} else {0i64}).bind(&weight).bind(&fee).bind(bn).bind(log_tx_hash(lg)).bind(now_iso()).bind(dedupe::chain_id()).bind(log_index_of(lg))
+        .execute(&st.db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn handle_signal_vote(st: &AppState, lg: &Log) -> Result<(), String> {
+    // topics: [sig, id, voter]; data: support(uint8), feePaid(uint256)
+    if lg.topics.len() < 3 { return Ok(()); }
+    let proposal_id = U256::from_big_endian(lg.topics[1].as_bytes()).low_u64() as i64;
+    let voter = Address::from_slice(&lg.topics[2].as_bytes()[12..]);
+    let toks = ethers::core::abi::decode(&[ParamType::Uint(8), ParamType::Uint(256)], &lg.data).map_err(|e| e.to_string())?;
+    let support = toks[0].clone().into_uint().unwrap_or_default().low_u64() as i64;
+    let fee = toks[1].clone().into_uint().unwrap_or_default().to_string();
+    let bn = lg.block_number.map(|b| b.as_u64() as i64).unwrap_or(0);
+    sqlx::query(&store::event_upsert(&st.db, "governance_signal_votes", &["proposal_id", "voter", "support", "weight", "fee_paid", "block_number", "tx_hash", "inserted_at", "chain_id", "log_index"]))
+        .bind(proposal_id).bind(format!("{:?}", voter)).bind(support).bind("1").bind(&fee).bind(bn).bind(log_tx_hash(lg)).bind(now_iso()).bind(dedupe::chain_id()).bind(log_index_of(lg))
+        .execute(&st.db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Both vote tables with a `source` column; support is 0 against, 1 for, 2 abstain (signals only).
+const VOTES_FROM: &str = "(SELECT proposal_id, voter, support, weight, fee_paid, block_number, log_index, tx_hash, finalized, 'governance' AS source FROM governance_votes \
+    UNION ALL SELECT proposal_id, voter, support, weight, fee_paid, block_number, log_index, tx_hash, finalized, 'signals' AS source FROM governance_signal_votes) v";
+
+// --- Tallies ---
+
+#[derive(Default)]
+struct Tally {
+    yes: U256,
+    no: U256,
+    fees: U256,
+    voters: HashSet<String>,
+    for_voters: usize,
+    against_voters: usize,
+}
+
+fn dec(s: &str) -> U256 { U256::from_dec_str(s).unwrap_or_default() }
+
+async fn tally(st: &AppState, proposal_id: i64) -> Tally {
+    // governance rows first, so a wallet that also signalled is counted by its VoteCast
+    let rows = sqlx::query(&format!("SELECT voter, support, weight, fee_paid FROM {} WHERE proposal_id=$1 ORDER BY source ASC, block_number ASC, log_index ASC", VOTES_FROM))
+        .bind(proposal_id).fetch_all(&st.db).await.unwrap_or_default();
+    let mut t = Tally::default();
+    for r in &rows {
+        let voter = r.get::<String,_>("voter").to_lowercase();
+        t.fees += dec(&r.get::<String,_>("fee_paid"));
+        // one vote per voter and contract on chain; a voter seen twice voted through both
+        if !t.voters.insert(voter) { continue; }
+        let w = dec(&r.get::<String,_>("weight"));
+        match r.get::<i64,_>("support") {
+            0 => { t.no += w; t.against_voters += 1; }
+            1 => { t.yes += w; t.for_voters += 1; }
+            _ => {}
+        }
+    }
+    t
+}
+
+// Counts only; the list view has no use for the voter addresses.
+#[derive(Default)]
+struct PageTally {
+    yes: U256,
+    no: U256,
+    voter_count: usize,
+    for_voters: usize,
+    against_voters: usize,
+}
+
+// Yes/no weight and voter counts of the newest `limit` proposals in one grouped query. A signal from
+// a wallet that also cast a VoteCast is left out, as in `tally`. Weights are U256 strings, so rows
+// are grouped by weight as well and summed here rather than in SQL.
+async fn page_tallies(st: &AppState, limit: i64) -> HashMap<i64, PageTally> {
+    let rows = sqlx::query("SELECT proposal_id, support, weight, COUNT(*) AS n FROM \
+        (SELECT proposal_id, support, weight FROM governance_votes \
+         UNION ALL SELECT s.proposal_id, s.support, s.weight FROM governance_signal_votes s \
+         WHERE NOT EXISTS (SELECT 1 FROM governance_votes g WHERE g.proposal_id = s.proposal_id AND LOWER(g.voter) = LOWER(s.voter))) v \
+        WHERE proposal_id IN (SELECT proposal_id FROM governance_proposals ORDER BY block_number DESC LIMIT $1) \
+        GROUP BY proposal_id, support, weight")
+        .bind(limit).fetch_all(&st.db).await.unwrap_or_default();
+    let mut out: HashMap<i64, PageTally> = HashMap::new();
+    for r in &rows {
+        let t = out.entry(r.get::<i64,_>("proposal_id")).or_default();
+        let n = r.get::<i64,_>("n") as usize;
+        let w = dec(&r.get::<String,_>("weight")) * U256::from(n);
+        match r.get::<i64,_>("support") {
+            0 => { t.no += w; t.against_voters += n; }
+            1 => { t.yes += w; t.for_voters += n; }
+            _ => {}
+        }
+        t.voter_count += n;
+    }
+    out
+}
+
+// --- Thresholds ---
+
+struct Threshold {
+    min_voters: u64,
+    quorum_votes: u64,
+    yes_bps: u64,
+    min_duration_sec: u64,
+    super_yes_bps: u64,
+}
+
+impl Threshold {
+    fn is_set(&self) -> bool {
+        self.min_voters != 0 || self.quorum_votes != 0 || self.yes_bps != 0 || self.min_duration_sec != 0 || self.super_yes_bps != 0
+    }
+}
+
+async fn call_threshold(provider: &Provider<Http>, gov: Address, sig: &str, args: &[u8]) -> Option<Threshold> {
+    let mut data = ethers::utils::id(sig).to_vec();
+    data.extend_from_slice(args);
+    let tx: ethers::types::transaction::eip2718::TypedTransaction = TransactionRequest::new().to(gov).data(data).into();
+    let out = provider.call(&tx, None).await.ok()?;
+    let toks = ethers::core::abi::decode(&[ParamType::Uint(32), ParamType::Uint(32), ParamType::Uint(16), ParamType::Uint(32), ParamType::Uint(16)], &out).ok()?;
+    let n = |i: usize| toks[i].clone().into_uint().unwrap_or_default().low_u64();
+    Some(Threshold { min_voters: n(0), quorum_votes: n(1), yes_bps: n(2), min_duration_sec: n(3), super_yes_bps: n(4) })
+}
+
+// (threshold, "key" | "default"), or None when the contract cannot be read.
+async fn threshold_for(st: &AppState, config_key: &str) -> Option<(Threshold, &'static str)> {
+    let gov: Address = read_state_string("/state/press_governance_address.txt")?.parse().ok()?;
+    let provider = Provider::<Http>::try_from(st.rpc_http.clone()).ok()?;
+    let key = crate::hex_to_bytes(config_key);
+    if key.len() == 32 {
+        if let Some(t) = call_threshold(&provider, gov, "thresholdByKey(bytes32)", &key).await.filter(|t| t.is_set()) {
+            return Some((t, "key"));
+        }
+    }
+    call_threshold(&provider, gov, "defaultThreshold()", &[]).await.map(|t| (t, "default"))
+}
+
+// Additional yes votes (nothing else changing) for yes / (yes + no) to reach `bps`; None if out of reach.
+fn yes_needed(yes: U256, no: U256, bps: u64) -> Option<U256> {
+    let total = yes + no;
+    let need = U256::from(bps) * total;
+    let have = U256::from(10_000u64) * yes;
+    if have >= need { return Some(U256::zero()); }
+    if bps >= 10_000 { return None; }
+    let per_vote = U256::from(10_000 - bps);
+    Some((need - have + per_vote - 1) / per_vote)
+}
+
+fn progress(t: &Threshold, tally: &Tally, created_at: i64, now: i64) -> serde_json::Value {
+    let total = tally.yes + tally.no;
+    let voters = tally.voters.len() as u64;
+    let quorum_remaining = U256::from(t.quorum_votes).saturating_sub(total);
+    let voters_remaining = t.min_voters.saturating_sub(voters);
+    let yes_bps_now = if total.is_zero() { None } else { Some((tally.yes * U256::from(10_000u64) / total).low_u64()) };
+    let yes_remaining = yes_needed(tally.yes, tally.no, t.yes_bps);
+    let elapsed = (now - created_at).max(0) as u64;
+    let duration_met = elapsed >= t.min_duration_sec;
+    let supermajority = t.super_yes_bps > 0 && voters_remaining == 0 && yes_bps_now.map(|b| b >= t.super_yes_bps).unwrap_or(false);
+    let met = quorum_remaining.is_zero() && voters_remaining == 0 && yes_remaining.map(|y| y.is_zero()).unwrap_or(false) && !total.is_zero();
+    json!({
+        "min_voters": t.min_voters,
+        "quorum_votes": t.quorum_votes,
+        "yes_bps": t.yes_bps,
+        "min_duration_sec": t.min_duration_sec,
+        "super_yes_bps": t.super_yes_bps,
+        "current_yes_bps": yes_bps_now,
+        "quorum_votes_remaining": quorum_remaining.to_string(),
+        "voters_remaining": voters_remaining,
+        "yes_votes_remaining": yes_remaining.map(|y| y.to_string()),
+        "min_duration_met": duration_met,
+        "supermajority": supermajority,
+        "met": met && (duration_met || supermajority),
+    })
+}
+
+// --- Endpoints ---
+
+fn vote_json(r: &store::DbRow, uq: &UnitsQ) -> serde_json::Value {
+    let support = match r.get::<i64,_>("support") { 0 => json!(false), 1 => json!(true), _ => json!(null) };
+    add_units(json!({
+        "proposal_id": r.get::<i64,_>("proposal_id"),
+        "voter": r.get::<String,_>("voter"),
+        "support": support,
+        "source": r.get::<String,_>("source"),
+        "weight": r.get::<String,_>("weight"),
+        "fee_paid": r.get::<String,_>("fee_paid"),
+        "block_number": r.get::<i64,_>("block_number"),
+        "tx_hash": r.get::<String,_>("tx_hash"),
+        "finalized": r.get::<i64,_>("finalized") != 0,
+    }), &[("fee_paid", NATIVE_DECIMALS)], uq)
+}
+
+const VOTE_COLS: &str = "proposal_id, voter, support, weight, fee_paid, block_number, tx_hash, finalized, source";
+
+// Proposal with live tallies, unique-voter counts, the finalized result if any, and how far it is
+// from the quorum / yes_bps / min_voters thresholds; the newest ?limit= votes are included.
+pub async fn proposal(State(st): State<AppState>, Path(id): Path<i64>, Query(lq): Query<LimitQ>, Query(uq): Query<UnitsQ>) -> Json<serde_json::Value> {
+    let row = sqlx::query("SELECT proposal_id, proposer, title, config_key, config_value, fee_paid, created_at, ends_at, block_number, finalized FROM governance_proposals WHERE proposal_id=$1")
+        .bind(id).fetch_optional(&st.db).await.ok().flatten();
+    let Some(p) = row else { return Json(json!({"ok": false, "error": "unknown proposal"})) };
+    let config_key = p.get::<String,_>("config_key");
+    let created_at = p.get::<String,_>("created_at").parse::<i64>().unwrap_or(0);
+    let ends_at = p.get::<String,_>("ends_at").parse::<i64>().unwrap_or(0);
+    let now = chrono::Utc::now().timestamp();
+
+    let t = tally(&st, id).await;
+    let result = sqlx::query("SELECT passed, auto_applied, reason, yes_votes, no_votes, block_number, tx_hash FROM approved_updates WHERE proposal_id=$1 ORDER BY block_number DESC LIMIT 1")
+        .bind(id).fetch_optional(&st.db).await.ok().flatten();
+    let thresholds = match threshold_for(&st, &config_key).await {
+        Some((th, source)) => { let mut v = progress(&th, &t, created_at, now); v["source"] = json!(source); v }
+        None => json!({"source": "unavailable"}),
+    };
+    let limit = lq.limit.unwrap_or(200).clamp(1, 1000);
+    let votes: Vec<serde_json::Value> = sqlx::query(&format!("SELECT {} FROM {} WHERE proposal_id=$1 ORDER BY block_number DESC, log_index DESC LIMIT $2", VOTE_COLS, VOTES_FROM))
+        .bind(id).bind(limit).fetch_all(&st.db).await.unwrap_or_default()
+        .iter().map(|r| vote_json(r, &uq)).collect();
+
+    Json(json!({
+        "ok": true,
+        "proposal": add_units(json!({
+            "proposal_id": id,
+            "proposer": p.get::<String,_>("proposer"),
+            "title": p.get::<String,_>("title"),
+            "config_key": config_key,
+            "config_value": p.get::<String,_>("config_value"),
+            "fee_paid": p.get::<String,_>("fee_paid"),
+            "created_at": created_at,
+            "ends_at": ends_at,
+            "voting_open": result.is_none() && now < ends_at,
+            "block_number": p.get::<i64,_>("block_number"),
+        }), &[("fee_paid", NATIVE_DECIMALS)], &uq),
+        "tally": add_units(json!({
+            "yes_votes": t.yes.to_string(),
+            "no_votes": t.no.to_string(),
+            "voter_count": t.voters.len(),
+            "for_voter_count": t.for_voters,
+            "against_voter_count": t.against_voters,
+            "fees_paid": t.fees.to_string(),
+        }), &[("fees_paid", NATIVE_DECIMALS)], &uq),
+        "result": result.map(|r| json!({
+            "passed": r.get::<i64,_>("passed") != 0,
+            "auto_applied": r.get::<i64,_>("auto_applied") != 0,
+            "reason": r.get::<String,_>("reason"),
+            "yes_votes": r.get::<String,_>("yes_votes"),
+            "no_votes": r.get::<String,_>("no_votes"),
+            "block_number": r.get::<i64,_>("block_number"),
+            "tx_hash": r.get::<String,_>("tx_hash"),
+        })),
+        "thresholds": thresholds,
+        "votes": votes,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct VotesQ {
+    proposal_id: Option<i64>,
+    voter: Option<String>,
+    limit: Option<i64>,
+}
+
+pub async fn votes(State(st): State<AppState>, Query(q): Query<VotesQ>, Query(uq): Query<UnitsQ>) -> Json<serde_json::Value> {
+    let limit = q.limit.unwrap_or(200).clamp(1, 1000);
+    let mut filter = String::from(" WHERE 1=1");
+    let mut n = 0;
+    if q.proposal_id.is_some() { n += 1; filter.push_str(&format!(" AND proposal_id=${}", n)); }
+    if q.voter.is_some() { n += 1; filter.push_str(&format!(" AND LOWER(voter)=${}", n)); }
+    let sql = format!("SELECT {} FROM {}{} ORDER BY block_number DESC, log_index DESC LIMIT ${}", VOTE_COLS, VOTES_FROM, filter, n + 1);
+    let count_sql = format!("SELECT COUNT(*) AS n FROM {}{}", VOTES_FROM, filter);
+    let mut query = sqlx::query(&sql);
+    let mut count = sqlx::query(&count_sql);
+    if let Some(id) = q.proposal_id { query = query.bind(id); count = count.bind(id); }
+    if let Some(v) = &q.voter { query = query.bind(v.to_lowercase()); count = count.bind(v.to_lowercase()); }
+    let items: Vec<serde_json::Value> = query.bind(limit).fetch_all(&st.db).await.unwrap_or_default()
+        .iter().map(|r| vote_json(r, &uq)).collect();
+    let total = count.fetch_one(&st.db).await.map(|r| r.get::<i64,_>("n")).unwrap_or(0);
+    Json(json!({"ok": true, "total": total, "items": items}))
+}
+
+// Newest proposals with their unique-voter counts, for list views that cannot afford a request per
+// proposal; thresholds and votes stay on /governance/proposals/:id.
+pub async fn proposals(State(st): State<AppState>, Query(lq): Query<LimitQ>, Query(uq): Query<UnitsQ>) -> Json<serde_json::Value> {
+    let limit = lq.limit.unwrap_or(50).clamp(1, 200);
+    let rows = sqlx::query("SELECT proposal_id, proposer, title, config_key, config_value, fee_paid, created_at, ends_at, block_number FROM governance_proposals ORDER BY block_number DESC LIMIT $1")
+        .bind(limit).fetch_all(&st.db).await.unwrap_or_default();
+    let mut tallies = page_tallies(&st, limit).await;
+    let mut items = Vec::with_capacity(rows.len());
+    for p in &rows {
+        let id = p.get::<i64,_>("proposal_id");
+        let t = tallies.remove(&id).unwrap_or_default();
+        items.push(add_units(json!({
+            "proposal_id": id,
+            "proposer": p.get::<String,_>("proposer"),
+            "title": p.get::<String,_>("title"),
+            "config_key": p.get::<String,_>("config_key"),
+            "config_value": p.get::<String,_>("config_value"),
+            "fee_paid": p.get::<String,_>("fee_paid"),
+            "created_at": p.get::<String,_>("created_at").parse::<i64>().unwrap_or(0),
+            "ends_at": p.get::<String,_>("ends_at").parse::<i64>().unwrap_or(0),
+            "block_number": p.get::<i64,_>("block_number"),
+            "yes_votes": t.yes.to_string(),
+            "no_votes": t.no.to_string(),
+            "voter_count": t.voter_count,
+            "for_voter_count": t.for_voters,
+            "against_voter_count": t.against_voters,
+        }), &[("fee_paid", NATIVE_DECIMALS)], &uq));
+    }
+    let total = sqlx::query("SELECT COUNT(*) AS n FROM governance_proposals").fetch_one(&st.db).await.map(|r| r.get::<i64,_>("n")).unwrap_or(0);
+    Json(json!({"ok": true, "total": total, "items": items}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn th(min_voters: u64, quorum_votes: u64, yes_bps: u64, min_duration_sec: u64, super_yes_bps: u64) -> Threshold {
+        Threshold { min_voters, quorum_votes, yes_bps, min_duration_sec, super_yes_bps }
+    }
+
+    fn votes(yes: u64, no: u64) -> Tally {
+        let mut t = Tally { yes: yes.into(), no: no.into(), ..Default::default() };
+        for i in 0..yes + no {
+            t.voters.insert(format!("0x{:040x}", i));
+            if i < yes { t.for_voters += 1 } else { t.against_voters += 1 }
+        }
+        t
+    }
+
+    fn needed(yes: u64, no: u64, bps: u64) -> Option<u64> {
+        yes_needed(yes.into(), no.into(), bps).map(|v| v.low_u64())
+    }
+
+    #[test]
+    fn yes_needed_is_zero_once_the_share_is_reached() {
+        assert_eq!(needed(1, 1, 5000), Some(0));
+        assert_eq!(needed(3, 1, 7500), Some(0));
+        assert_eq!(needed(1, 2, 5000), Some(1));
+    }
+
+    #[test]
+    fn yes_needed_rounds_up_to_whole_votes() {
+        // 3/5 = 60% exactly; 2/4 falls short
+        assert_eq!(needed(1, 2, 6000), Some(2));
+        // 2/3 = 66.66% is below 66.67%; 3/4 clears it
+        assert_eq!(needed(0, 1, 6667), Some(3));
+    }
+
+    #[test]
+    fn yes_needed_at_or_above_10000_bps() {
+        assert_eq!(needed(3, 0, 10_000), Some(0));
+        assert_eq!(needed(5, 1, 10_000), None);
+        assert_eq!(needed(3, 0, 12_000), None);
+    }
+
+    #[test]
+    fn yes_needed_with_zero_votes() {
+        assert_eq!(needed(0, 0, 5000), Some(0));
+        assert_eq!(needed(0, 0, 10_000), Some(0));
+    }
+
+    #[test]
+    fn progress_with_zero_votes_is_not_met() {
+        let p = progress(&th(0, 0, 5000, 0, 0), &votes(0, 0), 0, 100);
+        assert_eq!(p["current_yes_bps"], json!(null));
+        assert_eq!(p["yes_votes_remaining"], json!("0"));
+        assert_eq!(p["met"], json!(false));
+    }
+
+    #[test]
+    fn progress_rounds_the_current_share_down() {
+        let p = progress(&th(2, 3, 5000, 0, 0), &votes(2, 1), 0, 100);
+        assert_eq!(p["current_yes_bps"], json!(6666));
+        assert_eq!(p["quorum_votes_remaining"], json!("0"));
+        assert_eq!(p["voters_remaining"], json!(0));
+        assert_eq!(p["met"], json!(true));
+    }
+
+    #[test]
+    fn progress_reports_what_is_missing() {
+        let p = progress(&th(5, 10, 6000, 0, 0), &votes(1, 2), 0, 100);
+        assert_eq!(p["quorum_votes_remaining"], json!("7"));
+        assert_eq!(p["voters_remaining"], json!(2));
+        assert_eq!(p["yes_votes_remaining"], json!("2"));
+        assert_eq!(p["met"], json!(false));
+    }
+
+    #[test]
+    fn progress_waits_for_the_duration_unless_supermajority() {
+        let early = progress(&th(2, 3, 5000, 200, 0), &votes(2, 1), 0, 100);
+        assert_eq!(early["min_duration_met"], json!(false));
+        assert_eq!(early["met"], json!(false));
+        let sup = progress(&th(2, 3, 5000, 200, 6000), &votes(2, 1), 0, 100);
+        assert_eq!(sup["supermajority"], json!(true));
+        assert_eq!(sup["met"], json!(true));
+        // 100% is out of reach once there is a no vote
+        let all = progress(&th(0, 0, 10_000, 0, 0), &votes(2, 1), 0, 100);
+        assert_eq!(all["yes_votes_remaining"], json!(null));
+        assert_eq!(all["met"], json!(false));
+    }
+}
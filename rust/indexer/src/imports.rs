@@ -0,0 +1,447 @@
+// Arweave imports (LegacyMigrationEngine, PressArweaveImportRegistry).
+//
+// The arweave_imports stream records import jobs (ImportRequested with the fee paid and bond
+// locked, ImportCompleted with the anchored root hash), each registered article with its Arweave
+// txid and txid hash (ImportRegistered, priced at the ImportPolicyChanged policy in effect, else
+// config/arweave_import_policy.json) and the policy changes themselves.
+//
+// The verifier fetches the manifest of every completed job and recomputes its root: the leaves are
+// keccak256(arweaveTxId) of the manifest items that were registered, in manifest order, combined
+// pairwise as keccak256(min(a,b) ++ max(a,b)) with an odd node carried up unchanged (the
+// OpenZeppelin MerkleProof layout). A root or count that differs from ImportCompleted marks the job
+// as a mismatch and raises an oracle flag. Jobs that are not `ok` are checked again every round,
+// since their items may still be registered.
+
+use crate::store::{self, DbRow};
+use crate::{
+    add_units, dedupe, emit_flag, h256, log_index_of, log_tx_hash, now_iso, press_decimals, record_log_block,
+    topic_addr, AppState, BlockTimes, LimitQ, OracleFlag, UnitsQ, STREAM_IMPORTS,
+};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use ethers::core::abi::{ParamType, Token};
+use ethers::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+use sqlx::Row;
+
+const SIG_IMPORT_REQUESTED: &str = "ImportRequested(uint256,address,uint256,uint256,uint256,string)";
+const SIG_IMPORT_COMPLETED: &str = "ImportCompleted(uint256,bytes32,uint256)";
+const SIG_IMPORT_REGISTERED: &str = "ImportRegistered(address,bytes32,string,bytes32)";
+const SIG_IMPORT_POLICY_CHANGED: &str = "ImportPolicyChanged(uint256,uint256)";
+
+const JOB_COLS: [&str; 15] = [
+    "import_id", "event", "requester", "item_count", "fee_paid", "bond_locked", "manifest_uri", "root_hash",
+    "anchored_count", "block_time", "block_number", "log_index", "tx_hash", "inserted_at", "chain_id",
+];
+
+fn hex32(b: &[u8]) -> String { format!("0x{}", hex::encode(b)) }
+
+// Fee and bond the registry charged before any ImportPolicyChanged was seen.
+fn default_policy() -> (String, String) {
+    let v: serde_json::Value = std::fs::read_to_string("/state/arweave_import_policy.json")
+        .or_else(|_| std::fs::read_to_string("config/arweave_import_policy.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let get = |k: &str, d: &str| v.get(k).and_then(|x| x.as_str()).unwrap_or(d).to_string();
+    (get("import_fee_press_wei", "500000000000000000"), get("import_bond_press_wei", "2000000000000000000"))
+}
+
+// Policy in effect at (block, log index).
+async fn policy_at(st: &AppState, bn: i64, li: i64) -> (String, String) {
+    sqlx::query("SELECT fee, bond FROM arweave_import_policy WHERE block_number < $1 OR (block_number = $1 AND log_index < $2) ORDER BY block_number DESC, log_index DESC LIMIT 1")
+        .bind(bn).bind(li).fetch_optional(&st.db).await.ok().flatten()
+        .map(|r| (r.get::<String,_>("fee"), r.get::<String,_>("bond")))
+        .unwrap_or_else(default_policy)
+}
+
+pub async fn ingest_range(st: &AppState, provider: &Provider<Http>, addrs: &[Address], from_block: U64, to_block: U64) -> Result<usize, String> {
+    let topics = [SIG_IMPORT_REQUESTED, SIG_IMPORT_COMPLETED, SIG_IMPORT_REGISTERED, SIG_IMPORT_POLICY_CHANGED].map(h256);
+
+    let f = Filter::new()
+        .address(addrs.to_vec())
+        .from_block(from_block)
+        .to_block(to_block)
+        .topic0(ValueOrArray::Array(topics.to_vec()));
+
+    let logs = provider.get_logs(&f).await.map_err(|e| e.to_string())?;
+    let n = logs.len();
+    let mut times = BlockTimes::default();
+    for lg in logs {
+        record_log_block(st, STREAM_IMPORTS, &lg).await;
+        let block_time = times.of(provider, &lg).await?;
+        let t0 = lg.topics.first().cloned().unwrap_or_default();
+        let res = match topics.iter().position(|t| *t == t0) {
+            Some(i) => handle_log(st, i, &lg, block_time).await,
+            None => Ok(()),
+        };
+        if let Err(e) = res { eprintln!("arweave_imports: {e}"); }
+    }
+    Ok(n)
+}
+
+async fn handle_log(st: &AppState, i: usize, lg: &Log, block_time: i64) -> Result<(), String> {
+    let t = &lg.topics;
+    let bn = lg.block_number.map(|b| b.as_u64() as i64).unwrap_or(0);
+    let li = log_index_of(lg);
+    let tx = log_tx_hash(lg);
+    let dec = |types: &[ParamType]| ethers::core::abi::decode(types, &lg.data).map_err(|e| e.to_string());
+    let uint = |tok: &Token| tok.clone().into_uint().unwrap_or_default();
+    match i {
+        // ImportRequested(uint256 indexed importId, address indexed requester, uint256 itemCount,
+        //                 uint256 feePaid, uint256 bondLocked, string manifestUri)
+        0 if t.len() >= 3 => {
+            let toks = dec(&[ParamType::Uint(256), ParamType::Uint(256), ParamType::Uint(256), ParamType::String])?;
+            sqlx::query(&store::event_upsert(&st.db, "arweave_import_jobs", &JOB_COLS))
+                .bind(U256::from_big_endian(t[1].as_bytes()).to_string()).bind("requested").bind(topic_addr(&t[2]))
+                .bind(uint(&toks[0]).low_u64() as i64).bind(uint(&toks[1]).to_string()).bind(uint(&toks[2]).to_string())
+                .bind(toks[3].clone().into_string().unwrap_or_default()).bind("").bind(0i64)
+                .bind(block_time).bind(bn).bind(li).bind(&tx).bind(now_iso()).bind(dedupe::chain_id())
+                .execute(&st.db).await.map_err(|e| e.to_string())?;
+        }
+        // ImportCompleted(uint256 indexed importId, bytes32 rootHash, uint256 anchoredCount)
+        1 if t.len() >= 2 => {
+            let toks = dec(&[ParamType::FixedBytes(32), ParamType::Uint(256)])?;
+            sqlx::query(&store::event_upsert(&st.db, "arweave_import_jobs", &JOB_COLS))
+                .bind(U256::from_big_endian(t[1].as_bytes()).to_string()).bind("completed").bind("")
+                .bind(0i64).bind("0").bind("0").bind("")
+                .bind(hex32(&toks[0].clone().into_fixed_bytes().unwrap_or_default())).bind(uint(&toks[1]).low_u64() as i64)
+                .bind(block_time).bind(bn).bind(li).bind(&tx).bind(now_iso()).bind(dedupe::chain_id())
+                .execute(&st.db).await.map_err(|e| e.to_string())?;
+        }
+        // ImportRegistered(address indexed importer, bytes32 indexed articleId, string arweaveTxId, bytes32 arweaveTxIdHash)
+        2 if t.len() >= 3 => {
+            let toks = dec(&[ParamType::String, ParamType::FixedBytes(32)])?;
+            let (fee, bond) = policy_at(st, bn, li).await;
+            sqlx::query(&store::event_upsert(&st.db, "arweave_imports", &["importer", "article_id", "arweave_tx_id", "arweave_tx_id_hash", "fee_paid", "bond_required", "block_time", "block_number", "log_index", "tx_hash", "inserted_at", "chain_id"]))
+                .bind(topic_addr(&t[1])).bind(hex32(t[2].as_bytes()))
+                .bind(toks[0].clone().into_string().unwrap_or_default()).bind(hex32(&toks[1].clone().into_fixed_bytes().unwrap_or_default()))
+                .bind(fee).bind(bond)
+                .bind(block_time).bind(bn).bind(li).bind(&tx).bind(now_iso()).bind(dedupe::chain_id())
+                .execute(&st.db).await.map_err(|e| e.to_string())?;
+        }
+        // ImportPolicyChanged(uint256 feePress, uint256 bondPress)
+        3 => {
+            let toks = dec(&[ParamType::Uint(256), ParamType::Uint(256)])?;
+            sqlx::query(&store::event_upsert(&st.db, "arweave_import_policy", &["fee", "bond", "block_number", "log_index", "tx_hash", "inserted_at", "chain_id"]))
+                .bind(uint(&toks[0]).to_string()).bind(uint(&toks[1]).to_string())
+                .bind(bn).bind(li).bind(&tx).bind(now_iso()).bind(dedupe::chain_id())
+                .execute(&st.db).await.map_err(|e| e.to_string())?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// --- Manifest verification ---
+
+fn keccak(b: &[u8]) -> [u8; 32] {
+    Keccak256::digest(b).into()
+}
+
+pub fn manifest_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() { return [0u8; 32]; }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level.chunks(2).map(|p| match p {
+            [a, b] => {
+                let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+                keccak(&[lo.as_slice(), hi.as_slice()].concat())
+            }
+            [a] => *a,
+            _ => unreachable!(),
+        }).collect();
+    }
+    level[0]
+}
+
+fn gateway(var: &str, default: &str) -> String {
+    std::env::var(var).ok().filter(|s| !s.is_empty()).unwrap_or_else(|| default.to_string()).trim_end_matches('/').to_string()
+}
+
+// ar://<txid> and ipfs://<cid> go through ARWEAVE_GATEWAY / IPFS_GATEWAY; http(s) URIs are fetched as is.
+fn manifest_url(uri: &str) -> Result<String, String> {
+    if let Some(id) = uri.strip_prefix("ar://") { return Ok(format!("{}/{}", gateway("ARWEAVE_GATEWAY", "https://arweave.net"), id)); }
+    if let Some(cid) = uri.strip_prefix("ipfs://") { return Ok(format!("{}/ipfs/{}", gateway("IPFS_GATEWAY", "https://ipfs.io"), cid)); }
+    if uri.starts_with("http://") || uri.starts_with("https://") { return Ok(uri.to_string()); }
+    Err(format!("unsupported manifest uri {}", uri))
+}
+
+// Arweave txids listed by a manifest: {"items": [...]} or a bare array, whose items are either txid
+// strings or objects with arweaveTxId (or arweave_tx_id / txid).
+fn manifest_txids(v: &serde_json::Value) -> Vec<String> {
+    let items = v.get("items").unwrap_or(v).as_array().cloned().unwrap_or_default();
+    items.iter().filter_map(|it| match it {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Object(o) => ["arweaveTxId", "arweave_tx_id", "txid"].iter()
+            .find_map(|k| o.get(*k).and_then(|x| x.as_str()).map(|s| s.to_string())),
+        _ => None,
+    }).filter(|s| !s.is_empty()).collect()
+}
+
+async fn fetch_manifest(uri: &str) -> Result<Vec<String>, String> {
+    let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(30)).build().map_err(|e| e.to_string())?;
+    let resp = client.get(manifest_url(uri)?).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() { return Err(format!("manifest fetch: HTTP {}", resp.status())); }
+    let v: serde_json::Value = resp.json().await.map_err(|e| format!("manifest is not JSON: {}", e))?;
+    let txids = manifest_txids(&v);
+    if txids.is_empty() { return Err("manifest lists no items".into()); }
+    Ok(txids)
+}
+
+struct Job {
+    import_id: String,
+    root_hash: String,
+    anchored_count: i64,
+    manifest_uri: String,
+    completed_tx: String,
+}
+
+async fn save_verification(st: &AppState, job: &Job, status: &str, computed: &str, (manifest_items, registered): (i64, i64), missing: &[String], error: &str) {
+    let cols = ["import_id", "status", "anchored_root", "computed_root", "anchored_count", "manifest_items", "registered_items", "missing", "error", "completed_tx", "checked_at"];
+    let _ = sqlx::query(&store::upsert(&st.db, "arweave_import_verifications", &cols, &["import_id"]))
+        .bind(&job.import_id).bind(status).bind(&job.root_hash).bind(computed).bind(job.anchored_count)
+        .bind(manifest_items).bind(registered).bind(json!(missing).to_string()).bind(error).bind(&job.completed_tx).bind(now_iso())
+        .execute(&st.db).await;
+}
+
+async fn verify(st: &AppState, job: &Job) -> &'static str {
+    let txids = match fetch_manifest(&job.manifest_uri).await {
+        Ok(t) => t,
+        Err(e) => {
+            save_verification(st, job, "error", "", (0, 0), &[], &e).await;
+            return "error";
+        }
+    };
+    let mut leaves = vec![];
+    let mut missing = vec![];
+    for txid in &txids {
+        let leaf = keccak(txid.as_bytes());
+        let registered = sqlx::query("SELECT 1 FROM arweave_imports WHERE arweave_tx_id_hash=$1 LIMIT 1")
+            .bind(hex32(&leaf)).fetch_optional(&st.db).await.ok().flatten().is_some();
+        if registered { leaves.push(leaf); } else { missing.push(txid.clone()); }
+    }
+    let computed = hex32(&manifest_root(&leaves));
+    let root_ok = computed.eq_ignore_ascii_case(&job.root_hash);
+    let count_ok = leaves.len() as i64 == job.anchored_count;
+    let status = if root_ok && count_ok { "ok" } else { "mismatch" };
+
+    let previous: Option<String> = sqlx::query("SELECT status FROM arweave_import_verifications WHERE import_id=$1")
+        .bind(&job.import_id).fetch_optional(&st.db).await.ok().flatten().map(|r| r.get("status"));
+    save_verification(st, job, status, &computed, (txids.len() as i64, leaves.len() as i64), &missing, "").await;
+    if status == "mismatch" && previous.as_deref() != Some("mismatch") {
+        emit_flag(&st.db, OracleFlag {
+            ts: chrono::Utc::now().timestamp(),
+            article_id: format!("import:{}", job.import_id),
+            severity: 4,
+            kind: "import_root_mismatch".into(),
+            source: "indexer".into(),
+            details: json!({
+                "import_id": job.import_id,
+                "manifest_uri": job.manifest_uri,
+                "anchored_root": job.root_hash,
+                "computed_root": computed,
+                "anchored_count": job.anchored_count,
+                "registered_items": leaves.len(),
+                "manifest_items": txids.len(),
+                "missing": missing,
+            }),
+        }).await;
+        eprintln!("arweave_imports: import {} root mismatch (anchored {}, computed {})", job.import_id, job.root_hash, computed);
+    }
+    status
+}
+
+// Completed jobs never verified, not verified ok, or completed again in another tx since (reorg).
+async fn pending_jobs(st: &AppState) -> Vec<Job> {
+    sqlx::query(r#"
+        SELECT c.import_id, c.root_hash, c.anchored_count, c.tx_hash, r.manifest_uri
+        FROM arweave_import_jobs c
+        JOIN arweave_import_jobs r ON r.import_id = c.import_id AND r.event = 'requested'
+        LEFT JOIN arweave_import_verifications v ON v.import_id = c.import_id
+        WHERE c.event = 'completed' AND (v.import_id IS NULL OR v.status <> 'ok' OR v.completed_tx <> c.tx_hash)
+        ORDER BY c.block_number ASC
+        LIMIT 50
+    "#).fetch_all(&st.db).await.unwrap_or_default().iter().map(|r| Job {
+        import_id: r.get("import_id"),
+        root_hash: r.get("root_hash"),
+        anchored_count: r.get("anchored_count"),
+        manifest_uri: r.get("manifest_uri"),
+        completed_tx: r.get("tx_hash"),
+    }).collect()
+}
+
+fn verify_interval() -> u64 {
+    std::env::var("INDEXER_IMPORT_VERIFY_SECS").ok().and_then(|s| s.parse::<u64>().ok()).filter(|n| *n > 0).unwrap_or(300)
+}
+
+pub async fn verify_loop(st: AppState) {
+    loop {
+        for job in pending_jobs(&st).await {
+            verify(&st, &job).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(verify_interval())).await;
+    }
+}
+
+// --- HTTP ---
+
+fn verification_json(r: &DbRow) -> serde_json::Value {
+    json!({
+        "status": r.get::<String,_>("status"),
+        "anchored_root": r.get::<String,_>("anchored_root"),
+        "computed_root": r.get::<String,_>("computed_root"),
+        "manifest_items": r.get::<i64,_>("manifest_items"),
+        "registered_items": r.get::<i64,_>("registered_items"),
+        "missing": serde_json::from_str::<serde_json::Value>(&r.get::<String,_>("missing")).unwrap_or(json!([])),
+        "error": r.get::<String,_>("error"),
+        "checked_at": r.get::<String,_>("checked_at"),
+    })
+}
+
+// One job folded from its ImportRequested / ImportCompleted rows and its verification.
+async fn job_json(st: &AppState, import_id: &str, uq: &UnitsQ) -> Option<serde_json::Value> {
+    let rows = sqlx::query("SELECT * FROM arweave_import_jobs WHERE import_id=$1 ORDER BY block_number ASC, log_index ASC")
+        .bind(import_id).fetch_all(&st.db).await.unwrap_or_default();
+    let req = rows.iter().find(|r| r.get::<String,_>("event") == "requested");
+    let done = rows.iter().find(|r| r.get::<String,_>("event") == "completed");
+    if req.is_none() && done.is_none() { return None; }
+    let verification = sqlx::query("SELECT * FROM arweave_import_verifications WHERE import_id=$1")
+        .bind(import_id).fetch_optional(&st.db).await.ok().flatten().map(|r| verification_json(&r));
+    let v = json!({
+        "import_id": import_id,
+        "requester": req.map(|r| r.get::<String,_>("requester")),
+        "item_count": req.map(|r| r.get::<i64,_>("item_count")),
+        "fee_paid": req.map(|r| r.get::<String,_>("fee_paid")),
+        "bond_locked": req.map(|r| r.get::<String,_>("bond_locked")),
+        "manifest_uri": req.map(|r| r.get::<String,_>("manifest_uri")),
+        "requested_at": req.map(|r| r.get::<i64,_>("block_time")),
+        "request_tx": req.map(|r| r.get::<String,_>("tx_hash")),
+        "completed": done.is_some(),
+        "root_hash": done.map(|r| r.get::<String,_>("root_hash")),
+        "anchored_count": done.map(|r| r.get::<i64,_>("anchored_count")),
+        "completed_at": done.map(|r| r.get::<i64,_>("block_time")),
+        "complete_tx": done.map(|r| r.get::<String,_>("tx_hash")),
+        "verification": verification,
+    });
+    let dec = press_decimals();
+    Some(add_units(v, &[("fee_paid", dec), ("bond_locked", dec)], uq))
+}
+
+pub async fn jobs_latest(State(st): State<AppState>, Query(q): Query<LimitQ>, Query(uq): Query<UnitsQ>) -> Json<serde_json::Value> {
+    let limit = q.limit.unwrap_or(100).clamp(1, 500);
+    let ids = sqlx::query("SELECT import_id, MAX(block_number) AS b FROM arweave_import_jobs GROUP BY import_id ORDER BY b DESC LIMIT $1")
+        .bind(limit).fetch_all(&st.db).await.unwrap_or_default();
+    let mut items = vec![];
+    for r in &ids {
+        if let Some(j) = job_json(&st, &r.get::<String,_>("import_id"), &uq).await { items.push(j); }
+    }
+    Json(json!({"ok": true, "items": items}))
+}
+
+pub async fn job(State(st): State<AppState>, Path(import_id): Path<String>, Query(uq): Query<UnitsQ>) -> Json<serde_json::Value> {
+    match job_json(&st, &import_id, &uq).await {
+        Some(j) => Json(json!({"ok": true, "job": j})),
+        None => Json(json!({"ok": false, "error": "unknown import"})),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RegisteredQ {
+    importer: Option<String>,
+    article_id: Option<String>,
+    limit: Option<i64>,
+}
+
+pub async fn registered(State(st): State<AppState>, Query(q): Query<RegisteredQ>, Query(uq): Query<UnitsQ>) -> Json<serde_json::Value> {
+    let limit = q.limit.unwrap_or(200).clamp(1, 1000);
+    let rows = sqlx::query(r#"
+        SELECT importer, article_id, arweave_tx_id, arweave_tx_id_hash, fee_paid, bond_required, block_time, block_number, tx_hash
+        FROM arweave_imports
+        WHERE ($1 IS NULL OR lower(importer) = $1) AND ($2 IS NULL OR lower(article_id) = $2)
+        ORDER BY block_number DESC, log_index DESC
+        LIMIT $3
+    "#)
+        .bind(q.importer.map(|s| s.to_lowercase())).bind(q.article_id.map(|s| s.to_lowercase())).bind(limit)
+        .fetch_all(&st.db).await.unwrap_or_default();
+    let dec = press_decimals();
+    let items: Vec<serde_json::Value> = rows.iter().map(|r| add_units(json!({
+        "importer": r.get::<String,_>("importer"),
+        "article_id": r.get::<String,_>("article_id"),
+        "arweave_tx_id": r.get::<String,_>("arweave_tx_id"),
+        "arweave_tx_id_hash": r.get::<String,_>("arweave_tx_id_hash"),
+        "fee_paid": r.get::<String,_>("fee_paid"),
+        "bond_required": r.get::<String,_>("bond_required"),
+        "block_time": r.get::<i64,_>("block_time"),
+        "block_number": r.get::<i64,_>("block_number"),
+        "tx_hash": r.get::<String,_>("tx_hash"),
+    }), &[("fee_paid", dec), ("bond_required", dec)], &uq)).collect();
+    Json(json!({"ok": true, "items": items}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(txid: &str) -> [u8; 32] { keccak(txid.as_bytes()) }
+
+    fn pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+        keccak(&[lo, hi].concat())
+    }
+
+    // MerkleProof.processProof: fold the proof into the leaf with commutative keccak256
+    fn process_proof(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+        proof.iter().fold(leaf, |acc, p| pair(acc, *p))
+    }
+
+    #[test]
+    fn keccak_is_keccak256() {
+        assert_eq!(hex::encode(keccak(b"")), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+    }
+
+    #[test]
+    fn empty_manifest_has_zero_root() {
+        assert_eq!(manifest_root(&[]), [0u8; 32]);
+    }
+
+    #[test]
+    fn one_leaf_is_its_own_root() {
+        let a = leaf("tx-a");
+        assert_eq!(manifest_root(&[a]), a);
+        assert_eq!(process_proof(a, &[]), a);
+    }
+
+    #[test]
+    fn two_leaves_hash_sorted() {
+        let (a, b) = (leaf("tx-a"), leaf("tx-b"));
+        let root = manifest_root(&[a, b]);
+        assert_eq!(root, pair(a, b));
+        assert_eq!(root, manifest_root(&[b, a]));
+        assert_eq!(process_proof(a, &[b]), root);
+        assert_eq!(process_proof(b, &[a]), root);
+    }
+
+    #[test]
+    fn three_leaves_carry_the_odd_one_up() {
+        let (a, b, c) = (leaf("tx-a"), leaf("tx-b"), leaf("tx-c"));
+        let root = manifest_root(&[a, b, c]);
+        assert_eq!(root, pair(pair(a, b), c));
+        assert_eq!(process_proof(a, &[b, c]), root);
+        assert_eq!(process_proof(b, &[a, c]), root);
+        assert_eq!(process_proof(c, &[pair(a, b)]), root);
+    }
+
+    #[test]
+    fn four_leaves_make_a_full_tree() {
+        let (a, b, c, d) = (leaf("tx-a"), leaf("tx-b"), leaf("tx-c"), leaf("tx-d"));
+        let root = manifest_root(&[a, b, c, d]);
+        assert_eq!(root, pair(pair(a, b), pair(c, d)));
+        assert_eq!(process_proof(a, &[b, pair(c, d)]), root);
+        assert_eq!(process_proof(d, &[c, pair(a, b)]), root);
+        // manifest order decides the pairs
+        assert_ne!(root, manifest_root(&[a, c, b, d]));
+    }
+}
@@ -0,0 +1,449 @@
+// Declarative event recipes (recipes/*.json).
+//
+// A recipe names contracts (address from /state/deploy.json or literal), and for each event either a
+// human-readable signature or an ABI file to take it from, plus the target table and a column -> param
+// mapping. topic0 is computed from the event, indexed and non-indexed params are decoded with ethabi,
+// and the target table is created on startup. Each recipe runs as its own reorg-safe stream.
+
+use crate::getlogs::Window;
+use crate::{checkpoints, detect_reorg, finalize_tables, log_index_of, now_iso, record_log_block, record_tip, rollback_tables, store, AppState};
+use ethers::core::abi::{Abi, Event, ParamType, RawLog, Token};
+use ethers::prelude::*;
+use ethers::types::{Address, Filter, Log, H256, I256, U64};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Deserialize, Clone)]
+pub struct Recipe {
+    pub name: String,
+    #[serde(default)]
+    pub contracts: Vec<RecipeContract>,
+    #[serde(default)]
+    pub events: Vec<RecipeEvent>,
+}
+
+#[derive(Deserialize, Clone)]
+#[allow(non_snake_case)]
+pub struct RecipeContract {
+    pub name: String,
+    #[serde(default)]
+    pub addressFromState: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+    // file name under one of the ABI dirs; defaults to <name>.abi.json / <name>.json
+    #[serde(default)]
+    pub abi: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct RecipeEvent {
+    pub name: String,
+    // contract this event is emitted by; defaults to every contract in the recipe
+    #[serde(default)]
+    pub contract: Option<String>,
+    // e.g. "VoteCast(uint256 indexed id, address indexed voter, bool support, uint256 voteFeePaid)"
+    #[serde(default)]
+    pub signature: Option<String>,
+    // optional; checked against the computed value when it is a real hash
+    #[serde(default)]
+    pub topic0: Option<String>,
+    pub table: String,
+    // column -> event param name; empty means one column per param
+    #[serde(default)]
+    pub columns: BTreeMap<String, String>,
+}
+
+#[derive(Clone)]
+pub struct CompiledEvent {
+    pub name: String,
+    pub table: String,
+    pub topic0: H256,
+    pub contracts: Vec<RecipeContract>,
+    pub event: Event,
+    // (column, param index in event.inputs)
+    pub columns: Vec<(String, usize)>,
+}
+
+pub struct CompiledRecipe {
+    pub name: String,
+    pub source: String,
+    pub events: Vec<CompiledEvent>,
+    pub errors: Vec<String>,
+}
+
+enum SqlVal { Int(i64), Text(String) }
+
+fn recipe_dirs() -> Vec<String> {
+    match std::env::var("INDEXER_RECIPES_DIR") {
+        Ok(d) => vec![d],
+        Err(_) => vec!["/state/recipes".into(), "recipes".into()],
+    }
+}
+
+pub fn abi_dirs() -> Vec<String> {
+    vec!["/state/abi".into(), "contracts/abi".into(), "../../contracts/abi".into()]
+}
+
+// Accepts a plain ABI array as well as forge artifacts ({"abi": [...]}).
+pub fn load_abi(file: &str) -> Option<Abi> {
+    for dir in abi_dirs() {
+        let Ok(s) = std::fs::read_to_string(format!("{}/{}", dir, file)) else { continue };
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) else { continue };
+        let arr = if v.is_array() { v } else { v.get("abi").cloned().unwrap_or(serde_json::json!([])) };
+        if let Ok(abi) = serde_json::from_value::<Abi>(arr) { return Some(abi); }
+    }
+    None
+}
+
+fn contract_abi(c: &RecipeContract) -> Option<Abi> {
+    match &c.abi {
+        Some(f) => load_abi(f),
+        None => load_abi(&format!("{}.abi.json", c.name)).or_else(|| load_abi(&format!("{}.json", c.name))),
+    }
+}
+
+fn parse_signature(sig: &str) -> Result<Event, String> {
+    let abi = ethers::core::abi::parse_abi(&[format!("event {}", sig.trim()).as_str()]).map_err(|e| e.to_string())?;
+    abi.events().next().cloned().ok_or_else(|| format!("no event in signature {}", sig))
+}
+
+fn snake(name: &str) -> String {
+    let mut out = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if i > 0 { out.push('_'); }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+const TABLE_PREFIX: &str = "recipe_";
+
+fn valid_ident(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') && !s.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn compile_event(recipe: &Recipe, ev: &RecipeEvent) -> Result<CompiledEvent, String> {
+    if !valid_ident(&ev.table) { return Err(format!("{}: invalid table name {}", ev.name, ev.table)); }
+    // keeps recipes clear of the indexer's own tables and of the ops tables on a shared Postgres
+    if !ev.table.starts_with(TABLE_PREFIX) { return Err(format!("{}: table {} must start with {}", ev.name, ev.table, TABLE_PREFIX)); }
+    let contracts: Vec<RecipeContract> = recipe.contracts.iter()
+        .filter(|c| ev.contract.as_ref().map(|n| n == &c.name).unwrap_or(true))
+        .cloned().collect();
+    if contracts.is_empty() { return Err(format!("{}: no contract", ev.name)); }
+
+    let event = match &ev.signature {
+        Some(sig) => parse_signature(sig)?,
+        None => contracts.iter()
+            .filter_map(contract_abi)
+            .find_map(|abi| abi.event(&ev.name).ok().cloned())
+            .ok_or_else(|| format!("{}: no signature and no ABI entry", ev.name))?,
+    };
+    let topic0 = event.signature();
+    if let Some(t) = ev.topic0.as_ref().filter(|t| t.len() == 66 && t.starts_with("0x")) {
+        let given: H256 = t.parse().map_err(|_| format!("{}: bad topic0 {}", ev.name, t))?;
+        if given != topic0 { return Err(format!("{}: topic0 {} does not match computed {:?}", ev.name, t, topic0)); }
+    }
+
+    let mut columns = vec![];
+    if ev.columns.is_empty() {
+        for (i, p) in event.inputs.iter().enumerate() {
+            let col = if p.name.is_empty() { format!("arg{}", i) } else { snake(&p.name) };
+            columns.push((col, i));
+        }
+    } else {
+        for (col, param) in &ev.columns {
+            if !valid_ident(col) { return Err(format!("{}: invalid column {}", ev.name, col)); }
+            let i = event.inputs.iter().position(|p| &p.name == param)
+                .ok_or_else(|| format!("{}: unknown param {}", ev.name, param))?;
+            columns.push((col.clone(), i));
+        }
+    }
+
+    Ok(CompiledEvent { name: ev.name.clone(), table: ev.table.clone(), topic0, contracts, event, columns })
+}
+
+pub fn compile(source: &str, recipe: &Recipe) -> CompiledRecipe {
+    let mut events = vec![];
+    let mut errors = vec![];
+    for ev in &recipe.events {
+        match compile_event(recipe, ev) {
+            Ok(c) => events.push(c),
+            Err(e) => errors.push(e),
+        }
+    }
+    CompiledRecipe { name: recipe.name.clone(), source: source.to_string(), events, errors }
+}
+
+pub fn load_all() -> Vec<CompiledRecipe> {
+    let mut out = vec![];
+    for dir in recipe_dirs() {
+        let Ok(rd) = std::fs::read_dir(&dir) else { continue };
+        let mut paths: Vec<_> = rd.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| p.extension().map(|x| x == "json").unwrap_or(false)).collect();
+        paths.sort();
+        for p in paths {
+            let src = p.display().to_string();
+            match std::fs::read_to_string(&p).ok().and_then(|s| serde_json::from_str::<Recipe>(&s).ok()) {
+                Some(r) => out.push(compile(&src, &r)),
+                None => eprintln!("recipes: cannot parse {}", src),
+            }
+        }
+        if !out.is_empty() { break; }
+    }
+    out
+}
+
+fn sql_type(t: &ParamType) -> &'static str {
+    match t {
+        ParamType::Bool => "BIGINT",
+        _ => "TEXT",
+    }
+}
+
+pub fn token_json(t: &Token) -> serde_json::Value {
+    match t {
+        Token::Address(a) => serde_json::json!(format!("{:?}", a)),
+        Token::FixedBytes(b) | Token::Bytes(b) => serde_json::json!(format!("0x{}", hex::encode(b))),
+        Token::Uint(u) => serde_json::json!(u.to_string()),
+        Token::Int(i) => serde_json::json!(I256::from_raw(*i).to_string()),
+        Token::Bool(b) => serde_json::json!(b),
+        Token::String(s) => serde_json::json!(s),
+        Token::Array(v) | Token::FixedArray(v) | Token::Tuple(v) => serde_json::Value::Array(v.iter().map(token_json).collect()),
+    }
+}
+
+fn token_sql(t: &Token) -> SqlVal {
+    match t {
+        Token::Bool(b) => SqlVal::Int(if *b { 1 } else { 0 }),
+        Token::String(s) => SqlVal::Text(s.clone()),
+        Token::Array(_) | Token::FixedArray(_) | Token::Tuple(_) => SqlVal::Text(token_json(t).to_string()),
+        other => SqlVal::Text(token_json(other).as_str().unwrap_or_default().to_string()),
+    }
+}
+
+async fn ensure_table(st: &AppState, ev: &CompiledEvent) {
+    let cols: Vec<String> = ev.columns.iter()
+        .map(|(c, i)| format!("{} {}", c, sql_type(&ev.event.inputs[*i].kind)))
+        .collect();
+    let sql = format!(r#"
+        CREATE TABLE IF NOT EXISTS {} (
+            chain_id BIGINT NOT NULL DEFAULT 0,
+            block_number BIGINT NOT NULL,
+            tx_hash TEXT NOT NULL,
+            log_index BIGINT NOT NULL,
+            contract TEXT NOT NULL,
+            {}{}
+            finalized BIGINT NOT NULL DEFAULT 0,
+            inserted_at TEXT NOT NULL,
+            UNIQUE (chain_id, tx_hash, log_index)
+        );
+    "#, ev.table, cols.join(",\n            "), if cols.is_empty() { "" } else { "," });
+    if let Err(e) = sqlx::query(&sql).execute(&st.db).await {
+        eprintln!("recipes: create {} failed: {e}", ev.table);
+    }
+    // tables created before rows were keyed by chain
+    let _ = sqlx::query(&format!("ALTER TABLE {} ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0", ev.table)).execute(&st.db).await;
+    if let Err(e) = sqlx::query(&format!("CREATE UNIQUE INDEX IF NOT EXISTS uq_{0}_event ON {0} (chain_id, tx_hash, log_index)", ev.table)).execute(&st.db).await {
+        eprintln!("recipes: unique key on {} failed: {e}", ev.table);
+    }
+}
+
+fn resolve_address(c: &RecipeContract, deploy: &serde_json::Value) -> Option<Address> {
+    let raw = c.address.clone()
+        .or_else(|| c.addressFromState.as_ref().and_then(|k| deploy.get(k)).and_then(|v| v.as_str()).map(|s| s.to_string()))?;
+    raw.parse::<Address>().ok().filter(|a| !a.is_zero())
+}
+
+async fn insert_log(st: &AppState, ev: &CompiledEvent, lg: &Log) -> Result<(), String> {
+    let parsed = ev.event.parse_log(RawLog { topics: lg.topics.clone(), data: lg.data.to_vec() }).map_err(|e| e.to_string())?;
+    let mut names = vec!["chain_id".to_string(), "block_number".into(), "tx_hash".into(), "log_index".into(), "contract".into(), "inserted_at".into()];
+    let mut vals = vec![
+        SqlVal::Int(crate::dedupe::chain_id()),
+        SqlVal::Int(lg.block_number.map(|b| b.as_u64() as i64).unwrap_or(0)),
+        SqlVal::Text(lg.transaction_hash.map(|h| format!("{:?}", h)).unwrap_or_default()),
+        SqlVal::Int(log_index_of(lg)),
+        SqlVal::Text(format!("{:?}", lg.address)),
+        SqlVal::Text(now_iso()),
+    ];
+    for (col, i) in &ev.columns {
+        let pname = &ev.event.inputs[*i].name;
+        let tok = parsed.params.iter().find(|p| &p.name == pname).map(|p| &p.value)
+            .or_else(|| parsed.params.get(*i).map(|p| &p.value));
+        names.push(col.clone());
+        vals.push(tok.map(token_sql).unwrap_or(SqlVal::Text(String::new())));
+    }
+    let cols: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+    let sql = store::upsert(&st.db, &ev.table, &cols, &["chain_id", "tx_hash", "log_index"]);
+    let mut q = sqlx::query(&sql);
+    for v in vals {
+        q = match v { SqlVal::Int(n) => q.bind(n), SqlVal::Text(s) => q.bind(s) };
+    }
+    q.execute(&st.db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// (topic0, address) -> event, resolved against the current /state/deploy.json
+fn recipe_routes(recipe: &CompiledRecipe) -> Vec<(H256, Address, &CompiledEvent)> {
+    let deploy: serde_json::Value = std::fs::read_to_string("/state/deploy.json").ok()
+        .and_then(|s| serde_json::from_str(&s).ok()).unwrap_or(serde_json::json!({}));
+    let mut routes: Vec<(H256, Address, &CompiledEvent)> = vec![];
+    for ev in &recipe.events {
+        for c in &ev.contracts {
+            if let Some(a) = resolve_address(c, &deploy) { routes.push((ev.topic0, a, ev)); }
+        }
+    }
+    routes
+}
+
+pub fn addresses(recipe: &CompiledRecipe) -> Vec<Address> {
+    recipe_routes(recipe).into_iter().map(|r| r.1).collect()
+}
+
+pub fn recipe_tables(recipe: &CompiledRecipe) -> Vec<(&str, &str)> {
+    recipe.events.iter().map(|e| (e.table.as_str(), "block_number")).collect()
+}
+
+pub async fn ensure_tables(st: &AppState, recipe: &CompiledRecipe) {
+    for ev in &recipe.events { ensure_table(st, ev).await; }
+}
+
+// Reads and stores every log of the recipe in [from, to]. Inserts are keyed on
+// (tx_hash, log_index), so replaying a range is harmless.
+pub async fn ingest_range(st: &AppState, recipe: &CompiledRecipe, provider: &Provider<Http>, from: u64, to: u64) -> Result<usize, String> {
+    let stream = format!("recipe:{}", recipe.name);
+    let routes = recipe_routes(recipe);
+    if routes.is_empty() { return Err(format!("{stream}: no contract addresses resolved")); }
+
+    let mut addrs: Vec<Address> = routes.iter().map(|r| r.1).collect();
+    addrs.sort(); addrs.dedup();
+    let mut topics: Vec<H256> = routes.iter().map(|r| r.0).collect();
+    topics.sort(); topics.dedup();
+    let f = Filter::new()
+        .address(addrs)
+        .from_block(from)
+        .to_block(to)
+        .topic0(ValueOrArray::Array(topics));
+
+    let logs = provider.get_logs(&f).await.map_err(|e| e.to_string())?;
+    let n = logs.len();
+    for lg in logs {
+        let t0 = lg.topics.first().cloned().unwrap_or_default();
+        let Some((_, _, ev)) = routes.iter().find(|(t, a, _)| *t == t0 && *a == lg.address) else { continue };
+        record_log_block(st, &stream, &lg).await;
+        if let Err(e) = insert_log(st, ev, &lg).await {
+            eprintln!("{stream} {}: {e}", ev.name);
+        }
+    }
+    Ok(n)
+}
+
+pub async fn ingest_loop(st: AppState, recipe: Arc<CompiledRecipe>) {
+    for e in &recipe.errors { eprintln!("recipe {}: {}", recipe.name, e); }
+    if recipe.events.is_empty() {
+        eprintln!("recipe {}: no usable events (skipping)", recipe.name);
+        return;
+    }
+    ensure_tables(&st, &recipe).await;
+
+    let stream = format!("recipe:{}", recipe.name);
+    let tables = recipe_tables(&recipe);
+    let provider = Arc::new(Provider::<Http>::try_from(st.rpc_http.clone()).expect("provider"));
+
+    let mut from_block: U64 = match checkpoints::cursor(&st.db, &stream).await {
+        Some(n) => U64::from((n + 1) as u64),
+        None => provider.get_block_number().await.map(|h| h.saturating_sub(U64::from(2000u64))).unwrap_or_default(),
+    };
+
+    let mut window = Window::new(&stream);
+    loop {
+        let last = from_block.as_u64() as i64 - 1;
+        if last > 0 {
+            if let Some(fork) = detect_reorg(&st, &stream, last).await {
+                rollback_tables(&st, &stream, &tables, fork).await;
+                from_block = U64::from((fork + 1) as u64);
+                checkpoints::rewind(&st.db, &stream, fork).await;
+                continue;
+            }
+        }
+
+        if recipe_routes(&recipe).is_empty() {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            continue;
+        }
+
+        let head = match provider.get_block_number().await {
+            Ok(h) => h,
+            Err(e) => { eprintln!("{stream} head error: {e}"); window.fail(&st, &e.to_string()).await; continue; }
+        };
+        if head < from_block {
+            crate::ws::wait(&st, std::time::Duration::from_secs(3)).await;
+            continue;
+        }
+        let to_block = U64::from(window.end(from_block.as_u64(), head.as_u64()));
+
+        if let Err(e) = ingest_range(&st, &recipe, &provider, from_block.as_u64(), to_block.as_u64()).await {
+            window.err(&st, head.as_u64() as i64, last, &e).await;
+            continue;
+        }
+        record_tip(&st, &stream, to_block.as_u64() as i64).await;
+        finalize_tables(&st, &stream, &tables, head.as_u64() as i64, to_block.as_u64() as i64).await;
+        window.ok(&st, head.as_u64() as i64, to_block.as_u64() as i64);
+
+        checkpoints::advance(&st.db, &stream, to_block.as_u64() as i64).await;
+        from_block = to_block + U64::from(1u64);
+        if to_block >= head { crate::ws::wait(&st, std::time::Duration::from_secs(2)).await; }
+    }
+}
+
+fn column_json(row: &store::DbRow, col: &str, kind: Option<&ParamType>) -> serde_json::Value {
+    use sqlx::Row;
+    match kind {
+        Some(ParamType::Bool) => serde_json::json!(row.try_get::<i64, _>(col).map(|v| v != 0).unwrap_or(false)),
+        _ => serde_json::json!(row.try_get::<String, _>(col).unwrap_or_default()),
+    }
+}
+
+pub async fn rows_latest(st: &AppState, table: &str, limit: i64) -> Option<Vec<serde_json::Value>> {
+    use sqlx::Row;
+    let ev = st.recipes.iter().flat_map(|r| r.events.iter()).find(|e| e.table == table)?;
+    let sql = format!("SELECT * FROM {} ORDER BY block_number DESC, log_index DESC LIMIT $1", ev.table);
+    let rows = sqlx::query(&sql).bind(limit).fetch_all(&st.db).await.unwrap_or_default();
+    Some(rows.iter().map(|r| {
+        let mut m = serde_json::Map::new();
+        m.insert("block_number".into(), serde_json::json!(r.try_get::<i64, _>("block_number").unwrap_or(0)));
+        m.insert("tx_hash".into(), serde_json::json!(r.try_get::<String, _>("tx_hash").unwrap_or_default()));
+        m.insert("log_index".into(), serde_json::json!(r.try_get::<i64, _>("log_index").unwrap_or(0)));
+        m.insert("contract".into(), serde_json::json!(r.try_get::<String, _>("contract").unwrap_or_default()));
+        for (col, i) in &ev.columns {
+            m.insert(col.clone(), column_json(r, col, Some(&ev.event.inputs[*i].kind)));
+        }
+        m.insert("finalized".into(), serde_json::json!(r.try_get::<i64, _>("finalized").map(|v| v != 0).unwrap_or(false)));
+        m.insert("inserted_at".into(), serde_json::json!(r.try_get::<String, _>("inserted_at").unwrap_or_default()));
+        serde_json::Value::Object(m)
+    }).collect())
+}
+
+pub fn canonical_signature(ev: &Event) -> String {
+    let types: Vec<String> = ev.inputs.iter().map(|p| p.kind.to_string()).collect();
+    format!("{}({})", ev.name, types.join(","))
+}
+
+pub fn describe(r: &CompiledRecipe) -> serde_json::Value {
+    serde_json::json!({
+        "name": r.name,
+        "source": r.source,
+        "events": r.events.iter().map(|e| serde_json::json!({
+            "name": e.name,
+            "signature": canonical_signature(&e.event),
+            "topic0": format!("{:?}", e.topic0),
+            "table": e.table,
+            "contracts": e.contracts.iter().map(|c| c.name.clone()).collect::<Vec<_>>(),
+            "columns": e.columns.iter().map(|(c, i)| serde_json::json!({"column": c, "param": e.event.inputs[*i].name, "type": e.event.inputs[*i].kind.to_string(), "indexed": e.event.inputs[*i].indexed})).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "errors": r.errors,
+    })
+}
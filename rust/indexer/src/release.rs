@@ -0,0 +1,463 @@
+// Release batch lifecycle.
+//
+// A batch is put together from three sources:
+// - release_batches / release_batch_items: calendar-month batches ("YYYY-MM") the indexer plans when
+//   a batch key passes without being auto-applied (ProposalFinalized, governance stream);
+// - release_batch_queue_items: the items PressUpgradeQueue queues under keccak256("YYYY-MM"), which
+//   BatchQueued handling files under the same month (upgrade_queue stream);
+// - release_batch_events: ReleaseBatchManager's BatchCreated / ProposalQueued / ProposalUnqueued /
+//   BatchShipped, keyed by the manager's uint256 batch id.
+// A manager batch belongs to the month batch named in its label, else to the month batch holding the
+// first proposal it queues, else it stands alone as "manager-<id>". Proposal ids are taken to be
+// PressGovernance ids; ones governance_proposals does not know are listed without a config change.
+// The model is rebuilt on every read, so a rollback of either stream is reflected at once.
+//
+// States: planned (indexer only), queued (on chain, in the upgrade queue or the manager), ready
+// (manager batch past minReadyAt), shipped (BatchShipped, with releaseTag and notesCid).
+
+use crate::{dedupe, h256, load_presets, log_index_of, log_tx_hash, now_iso, store, AppState};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use ethers::core::abi::ParamType;
+use ethers::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::Row;
+
+const SIG_BATCH_CREATED: &str = "BatchCreated(uint256,uint256,string,uint256)";
+const SIG_PROPOSAL_QUEUED: &str = "ProposalQueued(uint256,uint256,address,uint8)";
+const SIG_PROPOSAL_UNQUEUED: &str = "ProposalUnqueued(uint256,uint256,string)";
+const SIG_BATCH_SHIPPED: &str = "BatchShipped(uint256,uint256,string,string)";
+
+pub fn topics() -> Vec<H256> {
+    [SIG_BATCH_CREATED, SIG_PROPOSAL_QUEUED, SIG_PROPOSAL_UNQUEUED, SIG_BATCH_SHIPPED].iter().map(|s| h256(s)).collect()
+}
+
+#[derive(Default)]
+struct ManagerEvent {
+    event: &'static str,
+    proposal_id: i64,
+    proposer: String,
+    p_type: i64,
+    label: String,
+    min_ready_at: i64,
+    release_tag: String,
+    notes_cid: String,
+    reason: String,
+    event_time: i64,
+}
+
+pub async fn handle_log(st: &AppState, lg: &Log, block_time: i64) -> Result<(), String> {
+    // topics: [sig, batchId, ...]
+    if lg.topics.len() < 2 { return Ok(()); }
+    let t0 = lg.topics[0];
+    let batch = U256::from_big_endian(lg.topics[1].as_bytes()).low_u64() as i64;
+    let topic_u = |i: usize| U256::from_big_endian(lg.topics[i].as_bytes()).low_u64() as i64;
+    let mut ev = ManagerEvent { event_time: block_time, ..Default::default() };
+    if t0 == h256(SIG_BATCH_CREATED) {
+        // data: createdAt, label, minReadyAt
+        let toks = ethers::core::abi::decode(&[ParamType::Uint(256), ParamType::String, ParamType::Uint(256)], &lg.data).map_err(|e| e.to_string())?;
+        ev.event = "created";
+        ev.event_time = toks[0].clone().into_uint().unwrap_or_default().low_u64() as i64;
+        ev.label = toks[1].clone().into_string().unwrap_or_default();
+        ev.min_ready_at = toks[2].clone().into_uint().unwrap_or_default().low_u64() as i64;
+    } else if t0 == h256(SIG_PROPOSAL_QUEUED) {
+        // topics: [sig, batchId, proposalId, proposer]; data: pType
+        if lg.topics.len() < 4 { return Ok(()); }
+        let toks = ethers::core::abi::decode(&[ParamType::Uint(8)], &lg.data).map_err(|e| e.to_string())?;
+        ev.event = "queued";
+        ev.proposal_id = topic_u(2);
+        ev.proposer = format!("{:?}", Address::from_slice(&lg.topics[3].as_bytes()[12..]));
+        ev.p_type = toks[0].clone().into_uint().unwrap_or_default().low_u64() as i64;
+    } else if t0 == h256(SIG_PROPOSAL_UNQUEUED) {
+        // topics: [sig, batchId, proposalId]; data: reason
+        if lg.topics.len() < 3 { return Ok(()); }
+        let toks = ethers::core::abi::decode(&[ParamType::String], &lg.data).map_err(|e| e.to_string())?;
+        ev.event = "unqueued";
+        ev.proposal_id = topic_u(2);
+        ev.reason = toks[0].clone().into_string().unwrap_or_default();
+    } else if t0 == h256(SIG_BATCH_SHIPPED) {
+        // data: shippedAt, releaseTag, notesCid
+        let toks = ethers::core::abi::decode(&[ParamType::Uint(256), ParamType::String, ParamType::String], &lg.data).map_err(|e| e.to_string())?;
+        ev.event = "shipped";
+        ev.event_time = toks[0].clone().into_uint().unwrap_or_default().low_u64() as i64;
+        ev.release_tag = toks[1].clone().into_string().unwrap_or_default();
+        ev.notes_cid = toks[2].clone().into_string().unwrap_or_default();
+    } else {
+        return Ok(());
+    }
+    let bn = lg.block_number.map(|b| b.as_u64() as i64).unwrap_or(0);
+    sqlx::query(&store::event_upsert(&st.db, "release_batch_events", &[
+        "manager_batch_id", "event", "proposal_id", "proposer", "p_type", "label", "min_ready_at", "release_tag", "notes_cid",
+        "reason", "event_time", "block_number", "log_index", "tx_hash", "inserted_at", "chain_id",
+    ]))
+        .bind(batch).bind(ev.event).bind(ev.proposal_id).bind(&ev.proposer).bind(ev.p_type).bind(&ev.label).bind(ev.min_ready_at)
+        .bind(&ev.release_tag).bind(&ev.notes_cid).bind(&ev.reason).bind(ev.event_time)
+        .bind(bn).bind(log_index_of(lg)).bind(log_tx_hash(lg)).bind(now_iso()).bind(dedupe::chain_id())
+        .execute(&st.db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Model ---
+
+#[derive(Default, Clone)]
+struct Item {
+    proposal_id: i64,
+    config_key: String,
+    config_value: String,
+    // planned | queued | unqueued
+    status: String,
+    queue_batch_id: String,
+    proposer: String,
+    p_type: Option<i64>,
+    unqueue_reason: String,
+}
+
+#[derive(Default)]
+struct Batch {
+    batch_id: String,
+    title: String,
+    window_start: String,
+    window_end: String,
+    notes: String,
+    manager_batch_id: Option<i64>,
+    label: String,
+    created_at: Option<i64>,
+    min_ready_at: Option<i64>,
+    shipped_at: Option<i64>,
+    release_tag: String,
+    notes_cid: String,
+    items: Vec<Item>,
+}
+
+// "2025-12"
+fn is_month(s: &str) -> bool {
+    s.len() == 7 && s.as_bytes()[4] == b'-' && s.chars().enumerate().all(|(i, c)| i == 4 || c.is_ascii_digit())
+}
+
+impl Batch {
+    fn month(&self) -> Option<&str> { Some(self.batch_id.as_str()).filter(|b| is_month(b)) }
+
+    fn queue_batch_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = vec![];
+        for it in &self.items {
+            if !it.queue_batch_id.is_empty() && !ids.contains(&it.queue_batch_id) { ids.push(it.queue_batch_id.clone()); }
+        }
+        ids
+    }
+
+    fn state(&self, now: i64) -> &'static str {
+        if self.shipped_at.is_some() { return "shipped"; }
+        if self.min_ready_at.map(|t| now >= t).unwrap_or(false) { return "ready"; }
+        if self.manager_batch_id.is_some() || self.items.iter().any(|i| i.status == "queued") { return "queued"; }
+        "planned"
+    }
+
+    fn sort_ts(&self) -> i64 {
+        self.created_at.or_else(|| chrono::DateTime::parse_from_rfc3339(&self.window_start).ok().map(|d| d.timestamp())).unwrap_or(0)
+    }
+
+    fn matches(&self, id: &str) -> bool {
+        self.batch_id == id
+            || self.manager_batch_id.map(|n| id == format!("manager-{}", n)).unwrap_or(false)
+            || self.items.iter().any(|i| i.queue_batch_id.eq_ignore_ascii_case(id))
+    }
+
+    fn json(&self, now: i64) -> serde_json::Value {
+        json!({
+            "batch_id": self.batch_id,
+            "month": self.month(),
+            "title": self.title,
+            "label": self.label,
+            "state": self.state(now),
+            "window_start": self.window_start,
+            "window_end": self.window_end,
+            "notes": self.notes,
+            "queue_batch_ids": self.queue_batch_ids(),
+            "manager_batch_id": self.manager_batch_id,
+            "created_at": self.created_at,
+            "min_ready_at": self.min_ready_at,
+            "ready_in_sec": self.min_ready_at.filter(|_| self.shipped_at.is_none()).map(|t| (t - now).max(0)),
+            "shipped_at": self.shipped_at,
+            "release_tag": self.release_tag,
+            "notes_cid": self.notes_cid,
+            "proposals": self.items.iter().map(|i| json!({
+                "proposal_id": i.proposal_id,
+                "config_key": i.config_key,
+                "config_value": i.config_value,
+                "status": i.status,
+                "queue_batch_id": i.queue_batch_id,
+                "proposer": i.proposer,
+                "p_type": i.p_type,
+                "unqueue_reason": i.unqueue_reason,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+// Manager batches folded from their events, in batch id order.
+async fn manager_batches(st: &AppState) -> Vec<Batch> {
+    let rows = sqlx::query("SELECT manager_batch_id, event, proposal_id, proposer, p_type, label, min_ready_at, release_tag, notes_cid, reason, event_time FROM release_batch_events ORDER BY manager_batch_id ASC, block_number ASC, log_index ASC")
+        .fetch_all(&st.db).await.unwrap_or_default();
+    let mut out: Vec<Batch> = vec![];
+    for r in &rows {
+        let n = r.get::<i64,_>("manager_batch_id");
+        if out.last().map(|b| b.manager_batch_id != Some(n)).unwrap_or(true) {
+            out.push(Batch { batch_id: format!("manager-{}", n), manager_batch_id: Some(n), ..Default::default() });
+        }
+        let Some(b) = out.last_mut() else { continue };
+        let pid = r.get::<i64,_>("proposal_id");
+        match r.get::<String,_>("event").as_str() {
+            "created" => {
+                b.label = r.get::<String,_>("label");
+                b.created_at = Some(r.get::<i64,_>("event_time"));
+                b.min_ready_at = Some(r.get::<i64,_>("min_ready_at"));
+            }
+            "queued" => {
+                b.items.retain(|i| i.proposal_id != pid);
+                b.items.push(Item {
+                    proposal_id: pid,
+                    status: "queued".into(),
+                    proposer: r.get::<String,_>("proposer"),
+                    p_type: Some(r.get::<i64,_>("p_type")),
+                    ..Default::default()
+                });
+            }
+            "unqueued" => {
+                if let Some(i) = b.items.iter_mut().find(|i| i.proposal_id == pid) {
+                    i.status = "unqueued".into();
+                    i.unqueue_reason = r.get::<String,_>("reason");
+                }
+            }
+            "shipped" => {
+                b.shipped_at = Some(r.get::<i64,_>("event_time"));
+                b.release_tag = r.get::<String,_>("release_tag");
+                b.notes_cid = r.get::<String,_>("notes_cid");
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+// A batch item as stored. A queued row is laid over the planned row of the same batch and proposal,
+// so a proposal queued without being planned first is listed too, and one whose queue entry was
+// rolled back falls back to planned.
+pub struct ItemRow {
+    pub batch_id: String,
+    pub created_at: String,
+    item: Item,
+}
+
+impl ItemRow {
+    pub fn json(&self) -> serde_json::Value {
+        json!({
+            "batch_id": self.batch_id,
+            "proposal_id": self.item.proposal_id,
+            "config_key": self.item.config_key,
+            "config_value": self.item.config_value,
+            "status": self.item.status,
+            "queue_batch_id": self.item.queue_batch_id,
+            "created_at": self.created_at,
+        })
+    }
+}
+
+pub async fn item_rows(st: &AppState) -> Vec<ItemRow> {
+    let mut out: Vec<ItemRow> = sqlx::query("SELECT batch_id, proposal_id, config_key, config_value, created_at FROM release_batch_items")
+        .fetch_all(&st.db).await.unwrap_or_default()
+        .iter().map(|r| ItemRow {
+            batch_id: r.get::<String,_>("batch_id"),
+            created_at: r.get::<String,_>("created_at"),
+            item: Item {
+                proposal_id: r.get::<i64,_>("proposal_id"),
+                config_key: r.get::<String,_>("config_key"),
+                config_value: r.get::<String,_>("config_value"),
+                status: "planned".into(),
+                ..Default::default()
+            },
+        }).collect();
+    let queued = sqlx::query("SELECT batch_id, queue_batch_id, proposal_id, config_key, config_value, inserted_at FROM release_batch_queue_items ORDER BY block_number ASC, log_index ASC")
+        .fetch_all(&st.db).await.unwrap_or_default();
+    for r in &queued {
+        let bid = r.get::<String,_>("batch_id");
+        let pid = r.get::<i64,_>("proposal_id");
+        let qb = r.get::<String,_>("queue_batch_id");
+        match out.iter_mut().find(|o| o.batch_id == bid && o.item.proposal_id == pid) {
+            Some(o) => {
+                o.item.status = "queued".into();
+                o.item.queue_batch_id = qb;
+            }
+            None => out.push(ItemRow {
+                batch_id: bid,
+                created_at: r.get::<String,_>("inserted_at"),
+                item: Item {
+                    proposal_id: pid,
+                    config_key: r.get::<String,_>("config_key"),
+                    config_value: r.get::<String,_>("config_value"),
+                    status: "queued".into(),
+                    queue_batch_id: qb,
+                    ..Default::default()
+                },
+            }),
+        }
+    }
+    out.sort_by_key(|o| o.item.proposal_id);
+    out
+}
+
+async fn load(st: &AppState) -> Vec<Batch> {
+    let mut batches: Vec<Batch> = sqlx::query("SELECT batch_id, title, window_start, window_end, notes FROM release_batches")
+        .fetch_all(&st.db).await.unwrap_or_default()
+        .iter().map(|r| Batch {
+            batch_id: r.get::<String,_>("batch_id"),
+            title: r.get::<String,_>("title"),
+            window_start: r.get::<String,_>("window_start"),
+            window_end: r.get::<String,_>("window_end"),
+            notes: r.get::<String,_>("notes"),
+            ..Default::default()
+        }).collect();
+    for ItemRow { batch_id: bid, item, .. } in item_rows(st).await {
+        match batches.iter_mut().find(|b| b.batch_id == bid) {
+            Some(b) => b.items.push(item),
+            None => batches.push(Batch { title: format!("Release Batch {}", bid), batch_id: bid, items: vec![item], ..Default::default() }),
+        }
+    }
+
+    for mb in manager_batches(st).await {
+        let unlinked = |b: &Batch| b.manager_batch_id.is_none();
+        let target = batches.iter().position(|b| unlinked(b) && b.month().map(|m| mb.label.contains(m)).unwrap_or(false))
+            .or_else(|| batches.iter().position(|b| unlinked(b) && mb.items.first()
+                .map(|first| b.items.iter().any(|i| i.proposal_id == first.proposal_id)).unwrap_or(false)));
+        let Some(t) = target else { batches.push(mb); continue };
+        let b = &mut batches[t];
+        b.manager_batch_id = mb.manager_batch_id;
+        b.label = mb.label;
+        b.created_at = mb.created_at;
+        b.min_ready_at = mb.min_ready_at;
+        b.shipped_at = mb.shipped_at;
+        b.release_tag = mb.release_tag;
+        b.notes_cid = mb.notes_cid;
+        for mi in mb.items {
+            match b.items.iter_mut().find(|i| i.proposal_id == mi.proposal_id) {
+                Some(i) => {
+                    i.status = mi.status;
+                    i.proposer = mi.proposer;
+                    i.p_type = mi.p_type;
+                    i.unqueue_reason = mi.unqueue_reason;
+                }
+                None => b.items.push(mi),
+            }
+        }
+    }
+
+    // manager-queued proposals carry no config change; take it from the proposal
+    for b in batches.iter_mut() {
+        for i in b.items.iter_mut().filter(|i| i.config_key.is_empty()) {
+            if let Some(r) = sqlx::query("SELECT config_key, config_value FROM governance_proposals WHERE proposal_id=$1")
+                .bind(i.proposal_id).fetch_optional(&st.db).await.ok().flatten() {
+                i.config_key = r.get::<String,_>("config_key");
+                i.config_value = r.get::<String,_>("config_value");
+            }
+        }
+    }
+    batches.sort_by(|a, b| b.sort_ts().cmp(&a.sort_ts()).then_with(|| b.batch_id.cmp(&a.batch_id)));
+    batches
+}
+
+// --- Endpoints ---
+
+#[derive(Deserialize)]
+pub struct BatchesQ {
+    state: Option<String>,
+}
+
+pub async fn batches(State(st): State<AppState>, Query(q): Query<BatchesQ>) -> Json<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    let items: Vec<serde_json::Value> = load(&st).await.iter()
+        .filter(|b| q.state.as_deref().map(|s| s == b.state(now)).unwrap_or(true))
+        .map(|b| b.json(now)).collect();
+    Json(json!({"ok": true, "batches": items}))
+}
+
+// :batch_id is the month ("2025-12"), "manager-<id>", or the upgrade queue's bytes32 batch id.
+pub async fn batch(State(st): State<AppState>, Path(batch_id): Path<String>) -> Json<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    match load(&st).await.iter().find(|b| b.matches(&batch_id)) {
+        Some(b) => Json(json!({"ok": true, "batch": b.json(now)})),
+        None => Json(json!({"ok": false, "error": "unknown batch"})),
+    }
+}
+
+// (preset key, label) of a keccak'd config key, from any preset list.
+fn preset_for(presets: &serde_json::Value, key_hex: &str) -> Option<(String, String)> {
+    let lists = presets.as_object()?;
+    lists.values().filter_map(|v| v.as_array()).flatten().find_map(|it| {
+        let k = it.get("key").and_then(|v| v.as_str())?;
+        let hx = format!("0x{}", hex::encode(ethers::utils::keccak256(k.as_bytes())));
+        hx.eq_ignore_ascii_case(key_hex).then(|| (k.to_string(), it.get("label").and_then(|v| v.as_str()).unwrap_or_default().to_string()))
+    })
+}
+
+// One entry per config key change in the batch (unqueued proposals listed apart), with the proposal
+// behind it and the value the key had from the last earlier passed proposal.
+pub async fn changelog(State(st): State<AppState>, Path(batch_id): Path<String>) -> Json<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    let batches = load(&st).await;
+    let Some(b) = batches.iter().find(|b| b.matches(&batch_id)) else {
+        return Json(json!({"ok": false, "error": "unknown batch"}));
+    };
+    let presets = load_presets();
+    let mut changes = vec![];
+    let mut dropped = vec![];
+    for i in &b.items {
+        if i.status == "unqueued" {
+            dropped.push(json!({"proposal_id": i.proposal_id, "config_key": i.config_key, "reason": i.unqueue_reason}));
+            continue;
+        }
+        let p = sqlx::query("SELECT title, proposer, created_at, ends_at FROM governance_proposals WHERE proposal_id=$1")
+            .bind(i.proposal_id).fetch_optional(&st.db).await.ok().flatten();
+        let result = sqlx::query("SELECT passed, yes_votes, no_votes, block_number, tx_hash FROM approved_updates WHERE proposal_id=$1 ORDER BY block_number DESC LIMIT 1")
+            .bind(i.proposal_id).fetch_optional(&st.db).await.ok().flatten();
+        let previous = if i.config_key.is_empty() { None } else {
+            sqlx::query("SELECT config_value FROM approved_updates WHERE config_key=$1 AND passed=1 AND proposal_id < $2 ORDER BY proposal_id DESC LIMIT 1")
+                .bind(&i.config_key).bind(i.proposal_id).fetch_optional(&st.db).await.ok().flatten()
+                .map(|r| r.get::<String,_>("config_value"))
+        };
+        let preset = preset_for(&presets, &i.config_key);
+        changes.push(json!({
+            "config_key": i.config_key,
+            "key": preset.as_ref().map(|(k, _)| k),
+            "label": preset.as_ref().map(|(_, l)| l),
+            "value": i.config_value,
+            "previous_value": previous,
+            "status": i.status,
+            "proposal": {
+                "proposal_id": i.proposal_id,
+                "title": p.as_ref().map(|r| r.get::<String,_>("title")),
+                "proposer": p.as_ref().map(|r| r.get::<String,_>("proposer")),
+                "created_at": p.as_ref().map(|r| r.get::<String,_>("created_at")),
+                "ends_at": p.as_ref().map(|r| r.get::<String,_>("ends_at")),
+                "passed": result.as_ref().map(|r| r.get::<i64,_>("passed") != 0),
+                "yes_votes": result.as_ref().map(|r| r.get::<String,_>("yes_votes")),
+                "no_votes": result.as_ref().map(|r| r.get::<String,_>("no_votes")),
+                "finalized_tx": result.as_ref().map(|r| r.get::<String,_>("tx_hash")),
+                "finalized_block": result.as_ref().map(|r| r.get::<i64,_>("block_number")),
+            },
+        }));
+    }
+    Json(json!({
+        "ok": true,
+        "batch_id": b.batch_id,
+        "title": if b.label.is_empty() { &b.title } else { &b.label },
+        "state": b.state(now),
+        "release_tag": b.release_tag,
+        "notes_cid": b.notes_cid,
+        "shipped_at": b.shipped_at,
+        "manager_batch_id": b.manager_batch_id,
+        "queue_batch_ids": b.queue_batch_ids(),
+        "generated_at": now_iso(),
+        "changes": changes,
+        "unqueued": dropped,
+    }))
+}
@@ -0,0 +1,113 @@
+// Schema migrations.
+//
+// The schema lives in migrations/<backend>/NNN_name.sql (one set per store::Dialect, same versions
+// and tables) and is applied by press_migrate, which records each file's version and checksum and
+// refuses to start on drift. SQLite DBs created before that (by the old CREATE TABLE IF NOT EXISTS /
+// ALTER TABLE code) are first brought to the shape of 001_baseline: amount columns rebuilt as TEXT,
+// columns added over time filled in, and the release_batches table that was declared with the wrong
+// columns dropped. The baseline then adopts them as version 1. On Postgres the only earlier tables
+// are the ops/db/migrations ones, whose outlets, outlet_tokens and court_cases predate the indexer's
+// and are moved aside.
+
+use crate::store::{self, columns, Db};
+use crate::{dedupe, stream_tables, AppState, STREAMS};
+use axum::{extract::State, Json};
+use serde_json::json;
+use sqlx::Row;
+
+pub async fn migrate(db: &Db) -> Result<press_migrate::Status, String> {
+    let dialect = store::dialect(db);
+    if press_migrate::is_unversioned(db).await {
+        eprintln!("migrations: adopting a {} DB created before versioned migrations", dialect.name());
+        if press_migrate::is_postgres(db) { adopt_postgres(db).await?; } else { adopt_legacy(db).await?; }
+    }
+    press_migrate::run(db, dialect.migrations()).await
+}
+
+pub async fn status(State(st): State<AppState>) -> Json<serde_json::Value> {
+    let dialect = store::dialect(&st.db);
+    let s = press_migrate::status(&st.db, dialect.migrations()).await;
+    Json(json!({"ok": s.ok(), "backend": dialect.name(), "migrations": s}))
+}
+
+async fn add_column(db: &Db, table: &str, col: &str, ddl: &str) -> Result<(), String> {
+    let have = columns(db, table).await;
+    // tables the old code never created are left to the baseline
+    if have.is_empty() || have.iter().any(|(c, _)| c == col) { return Ok(()); }
+    sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, col, ddl)).execute(db).await
+        .map(|_| ()).map_err(|e| format!("{}.{}: {}", table, col, e))
+}
+
+// Amount columns used to be BIGINT, which SQLite turns into lossy REALs for values past i64.
+// Rebuild those tables with TEXT columns so exact decimal strings survive.
+async fn rebuild_amount_columns(db: &Db) -> Result<(), String> {
+    let targets: [(&str, &[&str]); 3] = [
+        ("outlets", &["bond_paid", "fee_paid"]),
+        ("outlet_tokens", &["supply", "fee_paid"]),
+        ("token_listings", &["fee_paid"]),
+    ];
+    for (table, cols) in targets {
+        let needs = columns(db, table).await.iter()
+            .any(|(name, ty)| cols.contains(&name.as_str()) && !ty.eq_ignore_ascii_case("TEXT"));
+        if !needs { continue; }
+        let ddl: Option<String> = sqlx::query("SELECT sql FROM sqlite_master WHERE type='table' AND name=$1")
+            .bind(table).fetch_optional(db).await.ok().flatten().map(|r| r.get("sql"));
+        let Some(mut ddl) = ddl else { continue };
+        for c in cols {
+            ddl = ddl.replace(&format!("{} BIGINT", c), &format!("{} TEXT", c));
+        }
+        let ddl = ddl.replacen(table, &format!("{}__amounts", table), 1);
+        let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+        for sql in [
+            ddl,
+            format!("INSERT INTO {t}__amounts SELECT * FROM {t}", t = table),
+            format!("DROP TABLE {}", table),
+            format!("ALTER TABLE {t}__amounts RENAME TO {t}", t = table),
+        ] {
+            sqlx::query(&sql).execute(&mut *tx).await.map_err(|e| format!("rebuilding {}: {}", table, e))?;
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+async fn adopt_legacy(db: &Db) -> Result<(), String> {
+    rebuild_amount_columns(db).await?;
+
+    // release_batches was declared twice; the first (wrong) declaration won, so every insert into
+    // it failed and the table is empty. The baseline recreates it with the right columns.
+    let rb = columns(db, "release_batches").await;
+    if !rb.is_empty() && !rb.iter().any(|(c, _)| c == "title") {
+        sqlx::query("DROP TABLE release_batches").execute(db).await.map_err(|e| e.to_string())?;
+    }
+
+    add_column(db, "press_events", "canonical_text", "TEXT").await?;
+    add_column(db, "press_events", "content_hash", "TEXT").await?;
+    for table in ["governance_proposals", "approved_updates", "release_batch_items", "press_events"] {
+        add_column(db, table, "block_number", "BIGINT NOT NULL DEFAULT 0").await?;
+    }
+    for stream in STREAMS {
+        for (table, _) in stream_tables(stream) {
+            add_column(db, table, "finalized", "BIGINT NOT NULL DEFAULT 0").await?;
+        }
+    }
+    for (table, _, _) in dedupe::EVENT_TABLES {
+        add_column(db, table, "chain_id", "BIGINT NOT NULL DEFAULT 0").await?;
+        add_column(db, table, "tx_hash", "TEXT NOT NULL DEFAULT ''").await?;
+        add_column(db, table, "log_index", "BIGINT NOT NULL DEFAULT -1").await?;
+    }
+    Ok(())
+}
+
+// ops/db/migrations declared these with other shapes (outlet_id BIGINT, token_address, ...) and
+// nothing ever wrote them; they are renamed rather than dropped in case someone did.
+async fn adopt_postgres(db: &Db) -> Result<(), String> {
+    for table in ["outlets", "outlet_tokens", "court_cases"] {
+        let cols = columns(db, table).await;
+        if cols.is_empty() || cols.iter().any(|(c, _)| c == "tx_hash") { continue; }
+        eprintln!("migrations: renaming {t} (old query_api shape) to {t}_legacy", t = table);
+        sqlx::query(&format!("ALTER TABLE {t} RENAME TO {t}_legacy", t = table)).execute(db).await
+            .map_err(|e| format!("{}: {}", table, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+// Storage backends.
+//
+// The indexer writes to SQLite (one file; enough for a single outlet) or to Postgres (the cluster
+// database that query_api reads directly). Both are reached through sqlx's Any driver and share one
+// schema: the same tables, columns and `$N` placeholders. Idempotent inserts are
+// `INSERT ... ON CONFLICT ... DO UPDATE` on both (SQLite >= 3.24), so a re-read row keeps its id.
+// `Dialect` holds the few things the two disagree on: table introspection, full-text search and the
+// migration files. The backend follows the URL (PRESS_INDEXER_DB_URL, else DATABASE_URL), so
+// backfill scratch DBs stay SQLite even when the live DB is Postgres.
+
+use crate::dedupe;
+use press_migrate::Migration;
+use sqlx::Row;
+
+pub type Db = sqlx::AnyPool;
+pub type DbRow = sqlx::any::AnyRow;
+
+pub trait Dialect: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn migrations(&self) -> &'static [Migration];
+
+    // Query taking the table name as $1 and returning (name, type) rows.
+    fn columns_sql(&self) -> &'static str;
+
+    // Query taking the table name as $1 and returning a row when it exists.
+    fn table_exists_sql(&self) -> &'static str;
+
+    // Physical row id used to pick the surviving copy of duplicate rows.
+    fn row_id(&self) -> &'static str;
+
+    // Search expression for the words of a user query; the last word is a prefix match.
+    fn search_expr(&self, words: &[String]) -> String;
+
+    // Query over search_fts taking (expr, outlet, event, kind, from, to, limit) and returning kind,
+    // ref_id, article_id, outlet, event, ts, title_hl, snippet and score (higher is better).
+    fn search_sql(&self) -> &'static str;
+}
+
+fn placeholders(n: usize) -> String {
+    (1..=n).map(|i| format!("${}", i)).collect::<Vec<_>>().join(",")
+}
+
+pub struct Sqlite;
+
+pub struct Postgres;
+
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "baseline", sql: include_str!("../migrations/sqlite/001_baseline.sql") },
+    Migration { version: 2, name: "arweave_imports", sql: include_str!("../migrations/sqlite/002_arweave_imports.sql") },
+    Migration { version: 3, name: "heartbeat_caller", sql: include_str!("../migrations/sqlite/003_heartbeat_caller.sql") },
+    Migration { version: 4, name: "governance_votes", sql: include_str!("../migrations/sqlite/004_governance_votes.sql") },
+    Migration { version: 5, name: "release_batch_lifecycle", sql: include_str!("../migrations/sqlite/005_release_batch_lifecycle.sql") },
+    Migration { version: 6, name: "search_update_triggers", sql: include_str!("../migrations/sqlite/006_search_update_triggers.sql") },
+    Migration { version: 7, name: "release_batch_queue_items", sql: include_str!("../migrations/sqlite/007_release_batch_queue_items.sql") },
+    Migration { version: 8, name: "governance_signal_votes", sql: include_str!("../migrations/sqlite/008_governance_signal_votes.sql") },
+    Migration { version: 9, name: "stream_rollbacks", sql: include_str!("../migrations/sqlite/009_stream_rollbacks.sql") },
+];
+
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "baseline", sql: include_str!("../migrations/postgres/001_baseline.sql") },
+    Migration { version: 2, name: "arweave_imports", sql: include_str!("../migrations/postgres/002_arweave_imports.sql") },
+    Migration { version: 3, name: "heartbeat_caller", sql: include_str!("../migrations/postgres/003_heartbeat_caller.sql") },
+    Migration { version: 4, name: "governance_votes", sql: include_str!("../migrations/postgres/004_governance_votes.sql") },
+    Migration { version: 5, name: "release_batch_lifecycle", sql: include_str!("../migrations/postgres/005_release_batch_lifecycle.sql") },
+    Migration { version: 6, name: "search_update_triggers", sql: include_str!("../migrations/postgres/006_search_update_triggers.sql") },
+    Migration { version: 7, name: "release_batch_queue_items", sql: include_str!("../migrations/postgres/007_release_batch_queue_items.sql") },
+    Migration { version: 8, name: "governance_signal_votes", sql: include_str!("../migrations/postgres/008_governance_signal_votes.sql") },
+    Migration { version: 9, name: "stream_rollbacks", sql: include_str!("../migrations/postgres/009_stream_rollbacks.sql") },
+];
+
+impl Dialect for Sqlite {
+    fn name(&self) -> &'static str { "sqlite" }
+
+    fn migrations(&self) -> &'static [Migration] { SQLITE_MIGRATIONS }
+
+    fn columns_sql(&self) -> &'static str {
+        "SELECT name, type FROM pragma_table_info($1)"
+    }
+
+    fn table_exists_sql(&self) -> &'static str {
+        "SELECT 1 FROM sqlite_master WHERE type='table' AND name=$1"
+    }
+
+    fn row_id(&self) -> &'static str { "rowid" }
+
+    // every word is quoted so user input can't inject FTS5 operators
+    fn search_expr(&self, words: &[String]) -> String {
+        match words.split_last() {
+            Some((last, rest)) => {
+                let mut parts: Vec<String> = rest.iter().map(|w| format!("\"{}\"", w)).collect();
+                parts.push(format!("\"{}\"*", last));
+                parts.join(" ")
+            }
+            None => String::new(),
+        }
+    }
+
+    // bm25 with the title weighted highest; it is lower-is-better, so it is negated
+    fn search_sql(&self) -> &'static str {
+        r#"
+        SELECT kind, ref_id, article_id, outlet, event, ts,
+               highlight(search_fts, 6, '<mark>', '</mark>') AS title_hl,
+               snippet(search_fts, 7, '<mark>', '</mark>', '…', 16) AS snippet,
+               -bm25(search_fts, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 8.0, 1.0) AS score
+        FROM search_fts
+        WHERE search_fts MATCH $1
+          AND ($2 IS NULL OR outlet = $2)
+          AND ($3 IS NULL OR event = $3)
+          AND ($4 IS NULL OR kind = $4)
+          AND ($5 IS NULL OR CAST(ts AS BIGINT) >= $5)
+          AND ($6 IS NULL OR CAST(ts AS BIGINT) <= $6)
+        ORDER BY score DESC
+        LIMIT $7
+        "#
+    }
+}
+
+impl Dialect for Postgres {
+    fn name(&self) -> &'static str { "postgres" }
+
+    fn migrations(&self) -> &'static [Migration] { POSTGRES_MIGRATIONS }
+
+    fn columns_sql(&self) -> &'static str {
+        "SELECT CAST(column_name AS TEXT) AS name, CAST(data_type AS TEXT) AS type FROM information_schema.columns WHERE table_schema = current_schema() AND table_name = $1 ORDER BY ordinal_position"
+    }
+
+    fn table_exists_sql(&self) -> &'static str {
+        "SELECT 1 FROM information_schema.tables WHERE table_schema = current_schema() AND table_name = $1"
+    }
+
+    fn row_id(&self) -> &'static str { "ctid" }
+
+    // words become tsquery lexemes, so operators in user input are plain text
+    fn search_expr(&self, words: &[String]) -> String {
+        let quoted: Vec<String> = words.iter().map(|w| format!("'{}'", w.replace('\'', "''"))).collect();
+        match quoted.split_last() {
+            Some((last, rest)) => {
+                let mut parts = rest.to_vec();
+                parts.push(format!("{}:*", last));
+                parts.join(" & ")
+            }
+            None => String::new(),
+        }
+    }
+
+    // weights come from the generated doc column: title A, outlet B, body C
+    fn search_sql(&self) -> &'static str {
+        r#"
+        SELECT kind, ref_id, article_id, outlet, event, ts,
+               ts_headline('simple', title, to_tsquery('simple', $1), 'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') AS title_hl,
+               ts_headline('simple', body, to_tsquery('simple', $1), 'StartSel=<mark>, StopSel=</mark>, MaxWords=16, MinWords=8') AS snippet,
+               CAST(ts_rank(doc, to_tsquery('simple', $1)) AS DOUBLE PRECISION) AS score
+        FROM search_fts
+        WHERE doc @@ to_tsquery('simple', $1)
+          AND ($2::TEXT IS NULL OR outlet = $2)
+          AND ($3::TEXT IS NULL OR event = $3)
+          AND ($4::TEXT IS NULL OR kind = $4)
+          AND ($5::BIGINT IS NULL OR ts >= $5)
+          AND ($6::BIGINT IS NULL OR ts <= $6)
+        ORDER BY score DESC
+        LIMIT $7
+        "#
+    }
+}
+
+pub fn dialect(db: &Db) -> &'static dyn Dialect {
+    if press_migrate::is_postgres(db) { &Postgres } else { &Sqlite }
+}
+
+pub async fn connect(url: &str) -> Result<Db, String> {
+    press_migrate::connect(url).await
+}
+
+// INSERT of `cols` ($1..$n in order) that updates the row already holding `conflict`, a conflict
+// target such as "(proposal_id)" or "(chain_id, tx_hash, log_index) WHERE log_index >= 0". The row is
+// updated in place rather than replaced, so its id (a feed / SSE cursor) and search entry survive.
+// The target must match a unique index; dedupe::ensure_event_keys guarantees that for event tables.
+fn upsert_sql(table: &str, cols: &[&str], conflict: &str) -> String {
+    let set: Vec<String> = cols.iter().map(|c| format!("{c}=excluded.{c}")).collect();
+    format!("INSERT INTO {} ({}) VALUES ({}) ON CONFLICT {} DO UPDATE SET {}", table, cols.join(","), placeholders(cols.len()), conflict, set.join(", "))
+}
+
+// Event tables are keyed by (chain_id, tx_hash, log_index) plus their discriminator columns; legacy
+// rows (log_index -1) are outside the partial unique index.
+pub fn event_upsert(_db: &Db, table: &str, cols: &[&str]) -> String {
+    let extra = dedupe::EVENT_TABLES.iter().find(|(t, _, _)| *t == table).map(|(_, _, e)| *e).unwrap_or(&[]);
+    upsert_sql(table, cols, &format!("({}) WHERE log_index >= 0", dedupe::key_cols(extra)))
+}
+
+pub fn upsert(_db: &Db, table: &str, cols: &[&str], key: &[&str]) -> String {
+    upsert_sql(table, cols, &format!("({})", key.join(", ")))
+}
+
+pub async fn table_exists(db: &Db, table: &str) -> bool {
+    sqlx::query(dialect(db).table_exists_sql())
+        .bind(table).fetch_optional(db).await.ok().flatten().is_some()
+}
+
+pub async fn columns(db: &Db, table: &str) -> Vec<(String, String)> {
+    sqlx::query(dialect(db).columns_sql()).bind(table)
+        .fetch_all(db).await.unwrap_or_default()
+        .iter().map(|r| (r.get::<String,_>("name"), r.get::<String,_>("type"))).collect()
+}
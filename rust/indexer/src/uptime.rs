@@ -0,0 +1,238 @@
+// Uptime SLA from UptimeBeacon heartbeats.
+//
+// Each service is expected to send a heartbeat with a non-zero status at least every `interval`
+// seconds (INDEXER_HEARTBEAT_INTERVALS per service, else INDEXER_HEARTBEAT_INTERVAL_SECS, else 300,
+// the bots' default). A gap between two such heartbeats (or between the last one and now) longer
+// than the interval is an incident, and the time past the interval counts as downtime.
+// Availability per window is 1 - downtime / window, where a window never starts before the
+// service's first heartbeat. Heartbeats with status 0 are counted as degraded, not as up.
+
+use crate::AppState;
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::Row;
+
+const WINDOWS: [(&str, i64); 3] = [("24h", 86400), ("7d", 7 * 86400), ("30d", 30 * 86400)];
+const MAX_INCIDENTS: usize = 100;
+
+// INDEXER_HEARTBEAT_INTERVALS: comma-separated service=seconds
+fn expected_interval(service: &str) -> i64 {
+    let per_service = std::env::var("INDEXER_HEARTBEAT_INTERVALS").unwrap_or_default();
+    per_service.split(',')
+        .filter_map(|e| e.trim().split_once('='))
+        .find(|(s, _)| *s == service)
+        .and_then(|(_, n)| n.trim().parse::<i64>().ok())
+        .or_else(|| std::env::var("INDEXER_HEARTBEAT_INTERVAL_SECS").ok().and_then(|s| s.parse::<i64>().ok()))
+        .filter(|n| *n > 0)
+        .unwrap_or(300)
+}
+
+struct Incident { start: i64, end: Option<i64> }
+
+struct Sla {
+    // (ts, status) of every heartbeat in the longest window, oldest first
+    beats: Vec<(i64, i64)>,
+    // last up heartbeat before the longest window, if any
+    before: Option<i64>,
+    first_ever: Option<i64>,
+}
+
+impl Sla {
+    // Up heartbeats and the gaps between them, as (from, to) pairs ending at `now`.
+    fn gaps(&self, now: i64) -> Vec<(i64, i64)> {
+        let mut ups: Vec<i64> = self.before.into_iter().collect();
+        ups.extend(self.beats.iter().filter(|(_, s)| *s != 0).map(|(t, _)| *t));
+        let mut out: Vec<(i64, i64)> = ups.windows(2).map(|w| (w[0], w[1])).collect();
+        if let Some(last) = ups.last() { out.push((*last, now)); }
+        out
+    }
+
+    fn window(&self, now: i64, secs: i64, interval: i64) -> serde_json::Value {
+        let Some(first) = self.first_ever else { return json!({"availability": null}) };
+        let start = (now - secs).max(first);
+        let span = now - start;
+        if span <= 0 { return json!({"availability": null}); }
+        let gaps = self.gaps(now);
+        let mut down = 0;
+        let mut incidents = 0;
+        for &(from, to) in &gaps {
+            if to - from <= interval { continue; }
+            // downtime runs from the missed heartbeat to the next one, clipped to the window
+            let (a, b) = ((from + interval).max(start), to.min(now));
+            if b > a { down += b - a; incidents += 1; }
+        }
+        // no up heartbeat at all since the first one: down for the whole window
+        if gaps.is_empty() { down = span; }
+        let degraded = self.beats.iter().filter(|(t, s)| *s == 0 && *t >= start).count();
+        let heartbeats = self.beats.iter().filter(|(t, _)| *t >= start).count();
+        json!({
+            "availability": ((1.0 - down as f64 / span as f64) * 1_000_000.0).round() / 1_000_000.0,
+            "downtime_sec": down,
+            "incidents": incidents,
+            "heartbeats": heartbeats,
+            "degraded_heartbeats": degraded,
+            "from": start,
+        })
+    }
+
+    fn incidents(&self, now: i64, interval: i64) -> Vec<Incident> {
+        self.gaps(now).into_iter()
+            .filter(|(from, to)| to - from > interval)
+            .map(|(from, to)| Incident { start: from, end: if to == now { None } else { Some(to) } })
+            .collect()
+    }
+}
+
+async fn load(st: &AppState, service: &str, since: i64) -> Sla {
+    let beats = sqlx::query("SELECT ts, status FROM heartbeats WHERE service=$1 AND ts >= $2 ORDER BY ts ASC")
+        .bind(service).bind(since).fetch_all(&st.db).await.unwrap_or_default()
+        .iter().map(|r| (r.get::<i64,_>("ts"), r.get::<i64,_>("status"))).collect();
+    let before = sqlx::query("SELECT MAX(ts) AS ts FROM heartbeats WHERE service=$1 AND ts < $2 AND status <> 0")
+        .bind(service).bind(since).fetch_one(&st.db).await.ok()
+        .and_then(|r| r.get::<Option<i64>,_>("ts"));
+    let first_ever = sqlx::query("SELECT MIN(ts) AS ts FROM heartbeats WHERE service=$1")
+        .bind(service).fetch_one(&st.db).await.ok()
+        .and_then(|r| r.get::<Option<i64>,_>("ts"));
+    Sla { beats, before, first_ever }
+}
+
+async fn service_sla(st: &AppState, service: &str, interval: i64, now: i64) -> serde_json::Value {
+    let longest = WINDOWS.iter().map(|(_, s)| *s).max().unwrap_or(0);
+    let sla = load(st, service, now - longest).await;
+    let last = sqlx::query("SELECT ts, status, caller, block_number, tx_hash FROM heartbeats WHERE service=$1 ORDER BY ts DESC LIMIT 1")
+        .bind(service).fetch_optional(&st.db).await.ok().flatten();
+    let windows: serde_json::Map<String, serde_json::Value> = WINDOWS.iter()
+        .map(|(name, secs)| (name.to_string(), sla.window(now, *secs, interval)))
+        .collect();
+    let mut incidents = sla.incidents(now, interval);
+    incidents.reverse();
+    let incidents: Vec<serde_json::Value> = incidents.iter().take(MAX_INCIDENTS).map(|i| json!({
+        "start": i.start,
+        "end": i.end,
+        "duration_sec": i.end.unwrap_or(now) - i.start,
+        "ongoing": i.end.is_none(),
+    })).collect();
+    json!({
+        "service": service,
+        "expected_interval_sec": interval,
+        "last_heartbeat": last.as_ref().map(|r| json!({
+            "ts": r.get::<i64,_>("ts"),
+            "status": r.get::<i64,_>("status"),
+            "caller": r.get::<String,_>("caller"),
+            "block_number": r.get::<i64,_>("block_number"),
+            "tx_hash": r.get::<String,_>("tx_hash"),
+        })),
+        "last_heartbeat_age_sec": last.as_ref().map(|r| (now - r.get::<i64,_>("ts")).max(0)),
+        "windows": windows,
+        "incidents": incidents,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct SlaQ {
+    service: Option<String>,
+    // overrides the configured interval
+    interval: Option<i64>,
+}
+
+// Per-service availability over 24h / 7d / 30d, the incidents of the last 30 days (newest first)
+// and the age of the last heartbeat; every service that ever sent one unless ?service= is given.
+pub async fn sla(State(st): State<AppState>, Query(q): Query<SlaQ>) -> Json<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    let services: Vec<String> = match q.service {
+        Some(s) => vec![s],
+        None => sqlx::query("SELECT DISTINCT service FROM heartbeats ORDER BY service")
+            .fetch_all(&st.db).await.unwrap_or_default()
+            .iter().map(|r| r.get::<String,_>("service")).collect(),
+    };
+    let mut items = vec![];
+    for s in &services {
+        let interval = q.interval.filter(|n| *n > 0).unwrap_or_else(|| expected_interval(s));
+        items.push(service_sla(&st, s, interval, now).await);
+    }
+    Json(json!({"ok": true, "now": now, "services": items}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sla(beats: &[(i64, i64)], before: Option<i64>) -> Sla {
+        let first_ever = before.or(beats.first().map(|(t, _)| *t));
+        Sla { beats: beats.to_vec(), before, first_ever }
+    }
+
+    fn up(ts: &[i64]) -> Vec<(i64, i64)> { ts.iter().map(|t| (*t, 1)).collect() }
+
+    #[test]
+    fn no_heartbeats_has_no_availability() {
+        let w = sla(&[], None).window(1000, 1000, 100);
+        assert_eq!(w, json!({"availability": null}));
+    }
+
+    #[test]
+    fn only_degraded_heartbeats_are_down_for_the_whole_window() {
+        let w = sla(&[(0, 0), (100, 0), (200, 0)], None).window(1000, 1000, 100);
+        assert_eq!(w["availability"], json!(0.0));
+        assert_eq!(w["downtime_sec"], json!(1000));
+        assert_eq!(w["degraded_heartbeats"], json!(3));
+    }
+
+    #[test]
+    fn one_gap_over_the_interval_counts_the_time_past_it() {
+        let w = sla(&up(&[0, 100, 200, 500, 600, 700, 800, 900, 1000]), None).window(1000, 1000, 100);
+        assert_eq!(w["downtime_sec"], json!(200));
+        assert_eq!(w["incidents"], json!(1));
+        assert_eq!(w["availability"], json!(0.8));
+        assert_eq!(w["heartbeats"], json!(9));
+    }
+
+    #[test]
+    fn a_gap_equal_to_the_interval_is_not_an_incident() {
+        let w = sla(&up(&[0, 100, 200, 300]), None).window(300, 300, 100);
+        assert_eq!(w["downtime_sec"], json!(0));
+        assert_eq!(w["incidents"], json!(0));
+        assert_eq!(w["availability"], json!(1.0));
+    }
+
+    #[test]
+    fn adjacent_gaps_are_separate_incidents() {
+        let w = sla(&up(&[0, 300, 600]), None).window(600, 600, 100);
+        assert_eq!(w["downtime_sec"], json!(400));
+        assert_eq!(w["incidents"], json!(2));
+        assert_eq!(w["availability"], json!(0.333333));
+    }
+
+    #[test]
+    fn the_gap_since_the_last_heartbeat_runs_to_now() {
+        let w = sla(&up(&[0, 100, 200]), None).window(500, 500, 100);
+        assert_eq!(w["downtime_sec"], json!(200));
+        assert_eq!(w["incidents"], json!(1));
+    }
+
+    #[test]
+    fn a_window_never_starts_before_the_first_heartbeat() {
+        let w = sla(&up(&[800, 900, 1000]), None).window(1000, 1000, 100);
+        assert_eq!(w["from"], json!(800));
+        assert_eq!(w["availability"], json!(1.0));
+        assert_eq!(w["heartbeats"], json!(3));
+    }
+
+    #[test]
+    fn gaps_are_clipped_to_the_window_start() {
+        // the up heartbeat before the window opens the gap; only its part inside [500, 1000] counts
+        let w = sla(&up(&[1000]), Some(0)).window(1000, 500, 100);
+        assert_eq!(w["from"], json!(500));
+        assert_eq!(w["downtime_sec"], json!(500));
+        assert_eq!(w["availability"], json!(0.0));
+        assert_eq!(w["heartbeats"], json!(1));
+    }
+
+    #[test]
+    fn an_empty_window_has_no_availability() {
+        let w = sla(&up(&[1000]), None).window(1000, 86400, 100);
+        assert_eq!(w, json!({"availability": null}));
+    }
+}
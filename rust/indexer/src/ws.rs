@@ -0,0 +1,118 @@
+// Optional WebSocket mode (INDEXER_WS_URL).
+//
+// Subscribes to `newHeads` and to `logs` of every watched address and uses them to wake the stream
+// loops, which then read from their own cursor over HTTP exactly as in polling mode. The cursor is
+// what guarantees completeness: blocks produced while the socket was down are picked up on the next
+// wake-up, and reorg handling stays in one place. While disconnected the loops fall back to timed
+// polling; the socket reconnects with backoff and re-reads the watched addresses each time.
+
+use crate::{core_addresses, deploy_stream_addresses, DEPLOY_STREAMS, read_state_string, recipes, AppState};
+use ethers::prelude::*;
+use ethers::types::{Address, Filter};
+use futures_util::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+#[derive(Clone)]
+pub struct Live {
+    // latest head seen on the socket; also bumped (same value) when a watched log arrives
+    head: Arc<watch::Sender<u64>>,
+    connected: Arc<AtomicBool>,
+    enabled: bool,
+}
+
+impl Live {
+    pub fn new(enabled: bool) -> Self {
+        let (tx, _) = watch::channel(0u64);
+        Live { head: Arc::new(tx), connected: Arc::new(AtomicBool::new(false)), enabled }
+    }
+
+    pub fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "enabled": self.enabled,
+            "connected": self.connected.load(Ordering::Relaxed),
+            "head": *self.head.borrow(),
+        })
+    }
+}
+
+pub fn url() -> Option<String> {
+    std::env::var("INDEXER_WS_URL").ok().filter(|s| !s.trim().is_empty())
+}
+
+// Sleeps until the next head / watched log when the socket is up, or for `poll` otherwise.
+pub async fn wait(st: &AppState, poll: Duration) {
+    let live = &st.live;
+    if !live.connected.load(Ordering::Relaxed) {
+        tokio::time::sleep(poll).await;
+        return;
+    }
+    let mut rx = live.head.subscribe();
+    rx.borrow_and_update();
+    // the timeout only guards against a socket that silently stops delivering
+    let _ = tokio::time::timeout(Duration::from_secs(30), rx.changed()).await;
+}
+
+fn watched_addresses(st: &AppState) -> Vec<Address> {
+    let mut out: Vec<Address> = core_addresses().iter().filter_map(|a| a.parse().ok()).collect();
+    for s in DEPLOY_STREAMS { out.extend(deploy_stream_addresses(s)); }
+    for path in [
+        "/state/press_governance_address.txt",
+        "/state/press_upgrade_queue_address.txt",
+        "/state/exchange_listing_registry_address.txt",
+    ] {
+        if let Some(a) = read_state_string(path).and_then(|s| s.parse::<Address>().ok()) { out.push(a); }
+    }
+    for r in st.recipes.iter() { out.extend(recipes::addresses(r)); }
+    out.retain(|a| *a != Address::zero());
+    out.sort();
+    out.dedup();
+    out
+}
+
+async fn session(st: &AppState, url: &str) -> Result<(), String> {
+    let provider = Provider::<Ws>::connect(url).await.map_err(|e| e.to_string())?;
+    let mut heads = provider.subscribe_blocks().await.map_err(|e| e.to_string())?;
+    let addrs = watched_addresses(st);
+    let mut logs = if addrs.is_empty() {
+        None
+    } else {
+        Some(provider.subscribe_logs(&Filter::new().address(addrs)).await.map_err(|e| e.to_string())?)
+    };
+    st.live.connected.store(true, Ordering::Relaxed);
+    eprintln!("ws: subscribed to newHeads and logs on {}", url);
+
+    loop {
+        tokio::select! {
+            b = heads.next() => match b {
+                Some(block) => {
+                    let n = block.number.map(|n| n.as_u64()).unwrap_or_default();
+                    st.live.head.send_modify(|h| *h = (*h).max(n));
+                }
+                None => return Err("newHeads subscription closed".into()),
+            },
+            l = async { match logs.as_mut() { Some(s) => s.next().await, None => std::future::pending().await } } => match l {
+                Some(_) => st.live.head.send_modify(|_| {}),
+                None => return Err("logs subscription closed".into()),
+            },
+        }
+    }
+}
+
+pub async fn run(st: AppState, url: String) {
+    let mut failures = 0u32;
+    loop {
+        let started = std::time::Instant::now();
+        let res = session(&st, &url).await;
+        st.live.connected.store(false, Ordering::Relaxed);
+        // wake everyone so the loops switch to polling and fill the gap right away
+        st.live.head.send_modify(|_| {});
+        if started.elapsed() > Duration::from_secs(60) { failures = 0; }
+        failures = failures.saturating_add(1);
+        let secs = (1u64 << failures.min(6)).min(60);
+        eprintln!("ws: {} (falling back to HTTP polling, reconnect in {}s)", res.err().unwrap_or_default(), secs);
+        tokio::time::sleep(Duration::from_secs(secs)).await;
+    }
+}
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS oracle_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    content_hash TEXT NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT NOT NULL,
+    outlet TEXT NOT NULL,
+    author_wallet TEXT NOT NULL,
+    similarity_score REAL NOT NULL,
+    copyright_risk TEXT NOT NULL,
+    conflict_flags_json TEXT NOT NULL,
+    tags_json TEXT NOT NULL,
+    content_excerpt TEXT NOT NULL,
+    tokens_json TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
@@ -3,13 +3,14 @@ use axum::{routing::{get, post}, Json, Router, extract::{State}};
 use serde::{Deserialize, Serialize};
 use tower_http::cors::{CorsLayer, Any};
 use tracing_subscriber::EnvFilter;
-use sqlx::{SqlitePool, Row};
+use sqlx::{AnyPool, Row};
 use sha2::{Sha256, Digest};
 use std::collections::HashSet;
+use press_metrics::RouterExt;
 
 #[derive(Clone)]
 struct AppState {
-    db: SqlitePool,
+    db: AnyPool,
 }
 
 #[derive(Serialize)]
@@ -93,6 +94,7 @@ async fn analyze(State(st): State<AppState>, Json(req): Json<AnalyzeReq>) -> Jso
     let copyright_risk = if similarity_score >= 0.82 { "high" }
         else if similarity_score >= 0.62 { "medium" }
         else { "low" }.to_string();
+    press_metrics::oracle_analysis("copyright", &copyright_risk);
 
     let conflict_flags: Vec<String> = vec![]; // RR26 will add contradiction sources
     let suggested_tags = vec!["press".to_string(), "news".to_string(), "oracle".to_string()];
@@ -169,30 +171,26 @@ async fn latest(State(st): State<AppState>) -> Json<Vec<OracleReport>> {
     Json(out)
 }
 
+const MIGRATIONS: &[press_migrate::Migration] = &[
+    press_migrate::Migration { version: 1, name: "baseline", sql: include_str!("../migrations/001_baseline.sql") },
+];
+
+async fn migrations(State(st): State<AppState>) -> Json<serde_json::Value> {
+    let s = press_migrate::status(&st.db, MIGRATIONS).await;
+    Json(serde_json::json!({"ok": s.ok(), "migrations": s}))
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt().with_env_filter(EnvFilter::from_default_env()).init();
 
     let db_url = std::env::var("ORACLE_DB").unwrap_or_else(|_| "sqlite:/data/oracle.db".into());
-    let db = SqlitePool::connect(&db_url).await.expect("db");
-
-    sqlx::query(r#"
-        CREATE TABLE IF NOT EXISTS oracle_reports (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            content_hash TEXT NOT NULL,
-            url TEXT NOT NULL,
-            title TEXT NOT NULL,
-            outlet TEXT NOT NULL,
-            author_wallet TEXT NOT NULL,
-            similarity_score REAL NOT NULL,
-            copyright_risk TEXT NOT NULL,
-            conflict_flags_json TEXT NOT NULL,
-            tags_json TEXT NOT NULL,
-            content_excerpt TEXT NOT NULL,
-            tokens_json TEXT NOT NULL,
-            created_at TEXT NOT NULL
-        );
-    "#).execute(&db).await.ok();
+    let db = press_migrate::connect(&db_url).await.expect("db");
+
+    if let Err(e) = press_migrate::run(&db, MIGRATIONS).await {
+        eprintln!("oracle db: {}", e);
+        std::process::exit(1);
+    }
 
     let st = AppState{ db };
 
@@ -200,6 +198,8 @@ async fn main() {
         .route("/health", get(health))
         .route("/analyze", post(analyze))
         .route("/reports/latest", get(latest))
+        .route("/migrations", get(migrations))
+        .with_metrics("oracle")
         .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
         .with_state(st);
 
@@ -1,6 +1,7 @@
 use axum::{routing::{get, post}, Json, Router, extract::ConnectInfo};
 use serde::{Deserialize, Serialize};
 use std::{net::SocketAddr, time::Duration};
+use press_metrics::RouterExt;
 
 #[derive(Clone)]
 struct Cfg {
@@ -65,8 +66,10 @@ async fn health() -> Json<serde_json::Value> { Json(serde_json::json!({"ok": tru
 // - Copyright: if content includes obvious markers like "©" + long quote block (placeholder)
 // Optional: OpenAI integration can be enabled later by providing OPENAI_API_KEY; this service will not log it.
 async fn analyze(ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>, Json(req): Json<AnalyzeReq>, axum::extract::State(cfg): axum::extract::State<Cfg>) -> Json<AnalyzeResp> {
-    if !allow_key(&format!("ip:{}", addr.ip()), 30, 60) { return Json(AnalyzeResp{ok:false, flags: vec![]}); }
-    if !allow_key(&format!("article:{}", req.article_id), 10, 300) { return Json(AnalyzeResp{ok:false, flags: vec![]}); }
+    if !allow_key(&format!("ip:{}", addr.ip()), 30, 60) || !allow_key(&format!("article:{}", req.article_id), 10, 300) {
+        press_metrics::oracle_analysis("article", "rate_limited");
+        return Json(AnalyzeResp{ok:false, flags: vec![]});
+    }
     let mut flags = Vec::new();
     let content = req.content.clone().unwrap_or_default();
     let canonical = content.clone();
@@ -205,6 +208,7 @@ async fn analyze(ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>, Json(req)
             .send().await;
     }
 
+    press_metrics::oracle_analysis("article", if flags.is_empty() { "clean" } else { "flagged" });
     Json(AnalyzeResp{ ok: true, flags })
 }
 
@@ -226,6 +230,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/oracle/analyze", post(analyze))
         .route("/api/oracle/ingest_url", post(ingest_url))
         .route("/api/oracle/analyze_sources", post(analyze_sources))
+        .with_metrics("oracle_service")
         .with_state(cfg);
 
     let addr: SocketAddr = "0.0.0.0:8790".parse().unwrap();
@@ -333,14 +338,23 @@ fn allow_budget(cfg: &Cfg, add_calls: u32, add_tokens: u32) -> bool {
         st.calls = 0;
         st.tokens = 0;
     }
-    if st.calls + add_calls > cfg.daily_max_calls { return false; }
-    if st.tokens + add_tokens > cfg.daily_max_tokens { return false; }
+    if st.calls + add_calls > cfg.daily_max_calls || st.tokens + add_tokens > cfg.daily_max_tokens {
+        press_metrics::oracle_budget_denied();
+        report_budget(cfg, &st);
+        return false;
+    }
     st.calls += add_calls;
     st.tokens += add_tokens;
     save_budget(cfg, &st);
+    report_budget(cfg, &st);
     true
 }
 
+fn report_budget(cfg: &Cfg, st: &BudgetState) {
+    press_metrics::oracle_budget("calls", st.calls as u64, cfg.daily_max_calls as u64);
+    press_metrics::oracle_budget("tokens", st.tokens as u64, cfg.daily_max_tokens as u64);
+}
+
 fn redact_secrets(s: &str) -> String {
     // basic redactions: OpenAI keys, hex private keys, JWTs
     let mut out = s.to_string();
@@ -417,10 +431,14 @@ async fn analyze_sources(
     axum::extract::State(cfg): axum::extract::State<Cfg>,
     Json(req): Json<AnalyzeSourcesReq>
 ) -> Json<serde_json::Value> {
-    if !allow_key(&format!("ip:{}", addr.ip()), 30, 60) { return Json(serde_json::json!({"ok": false, "error":"rate_limited"})); }
+    if !allow_key(&format!("ip:{}", addr.ip()), 30, 60) {
+        press_metrics::oracle_analysis("sources", "rate_limited");
+        return Json(serde_json::json!({"ok": false, "error":"rate_limited"}));
+    }
 
     let body = serde_json::json!({"article_id":req.article_id,"outlet":req.outlet,"title":req.title,"urls":req.urls}).to_string();
     if !require_sig(&cfg, req.sig.clone(), &body) {
+        press_metrics::oracle_analysis("sources", "invalid_signature");
         return Json(serde_json::json!({"ok": false, "error":"invalid signature"}));
     }
 
@@ -493,5 +511,6 @@ async fn analyze_sources(
             .send().await;
     }
 
+    press_metrics::oracle_analysis("sources", if flags.is_empty() { "clean" } else { "flagged" });
     Json(serde_json::json!({"ok": true, "flags": flags}))
 }
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use tower_http::cors::{CorsLayer, Any};
 use tracing_subscriber::EnvFilter;
 use std::{path::PathBuf, net::SocketAddr};
+use press_metrics::RouterExt;
 
 #[derive(Clone)]
 struct AppState {
@@ -105,6 +106,7 @@ async fn main() {
     let app = Router::new()
         .route("/health", get(health))
         .route("/allow", post(allow))
+        .with_metrics("policy_api")
         .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
         .with_state(st);
 
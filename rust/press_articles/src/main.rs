@@ -4,6 +4,7 @@ use rusqlite::{Connection, params};
 use std::{net::SocketAddr, sync::Arc};
 use tokio::sync::Mutex;
 use uuid::Uuid;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct Health { ok: bool }
@@ -144,6 +145,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/v1/articles/submit", post(submit))
         .route("/v1/articles/vote", post(vote))
         .route("/v1/articles/:id", get(status))
+        .with_metrics("press_articles")
         .with_state(db);
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
     Ok(())
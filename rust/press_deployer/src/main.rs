@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{net::SocketAddr, path::PathBuf};
 use tokio::process::Command;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct OkResp { ok: bool }
@@ -189,7 +190,8 @@ let app = Router::new()
     .route("/v1/deploy-start", post(deploy_start))
     .route("/v1/deploy", post(deploy))
     .route("/v1/runtime", get(logs))
-    .route("/v1/fix", post(fix));
+    .route("/v1/fix", post(fix))
+    .with_metrics("press_deployer");
   axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
   Ok(())
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use rusqlite::{Connection, params};
 use std::{net::SocketAddr, sync::Arc};
 use tokio::sync::Mutex;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct OkResp { ok: bool }
@@ -80,6 +81,7 @@ async fn main() -> anyhow::Result<()> {
     let app = Router::new()
         .route("/health", get(health))
         .route("/proposals", get(list).post(create))
+        .with_metrics("press_governance")
         .with_state(db);
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
     Ok(())
@@ -2,6 +2,7 @@ use axum::{routing::get, Json, Router};
 use serde::Serialize;
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
+use press_metrics::RouterExt;
 
 #[derive(Default, Clone)]
 struct State {
@@ -62,6 +63,7 @@ async fn main() -> anyhow::Result<()> {
     let app = Router::new()
         .route("/health", get(health))
         .route("/stats", get(stats))
+        .with_metrics("press_indexer")
         .with_state(st);
 
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
@@ -0,0 +1,295 @@
+// Prometheus metrics shared by the Rust services.
+//
+// Every service wraps its Router with `.with_metrics("<service>")`, which times each request and
+// serves the registry as text exposition on GET /metrics. Every series carries a `service` label so
+// one scrape config covers the whole stack. Names are `press_<area>_<what>_<unit>`: counters end in
+// `_total`, durations are histograms in seconds. The domain helpers below are the only place metric
+// names are spelled out, so the indexer, bots, oracle and deployer report under the same names
+// wherever they run. The registry is process-global; series are created on first use.
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const HTTP_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+const STEP_BUCKETS: &[f64] = &[1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind { Counter, Gauge, Histogram }
+
+impl Kind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+            Kind::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Series {
+    // counter / gauge value; histogram sum
+    value: f64,
+    // histogram only: per-bucket (non-cumulative) counts and the total count
+    buckets: Vec<u64>,
+    count: u64,
+}
+
+struct Family {
+    help: &'static str,
+    kind: Kind,
+    bounds: &'static [f64],
+    series: BTreeMap<Vec<(String, String)>, Series>,
+}
+
+static REGISTRY: Mutex<BTreeMap<&'static str, Family>> = Mutex::new(BTreeMap::new());
+static SERVICE: OnceLock<&'static str> = OnceLock::new();
+
+fn key(labels: &[(&str, &str)]) -> Vec<(String, String)> {
+    labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn with_series(name: &'static str, help: &'static str, kind: Kind, bounds: &'static [f64], labels: &[(&str, &str)], f: impl FnOnce(&mut Series)) {
+    let mut reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let fam = reg.entry(name).or_insert_with(|| Family { help, kind, bounds, series: BTreeMap::new() });
+    // a name is registered once with one type; a clash is a bug in the caller, not worth a panic
+    if fam.kind != kind { return; }
+    let n = fam.bounds.len();
+    let s = fam.series.entry(key(labels)).or_insert_with(|| Series { buckets: vec![0; n], ..Default::default() });
+    f(s);
+}
+
+pub fn counter_add(name: &'static str, help: &'static str, labels: &[(&str, &str)], v: f64) {
+    with_series(name, help, Kind::Counter, &[], labels, |s| s.value += v.max(0.0));
+}
+
+pub fn gauge_set(name: &'static str, help: &'static str, labels: &[(&str, &str)], v: f64) {
+    with_series(name, help, Kind::Gauge, &[], labels, |s| s.value = v);
+}
+
+pub fn observe(name: &'static str, help: &'static str, bounds: &'static [f64], labels: &[(&str, &str)], v: f64) {
+    with_series(name, help, Kind::Histogram, bounds, labels, |s| {
+        if let Some(i) = bounds.iter().position(|b| v <= *b) { s.buckets[i] += 1; }
+        s.value += v;
+        s.count += 1;
+    });
+}
+
+// Sum of a counter over the series whose labels match every `(name, value)` in `filter`.
+pub fn counter_sum(name: &str, filter: &[(&str, &str)]) -> f64 {
+    let reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(fam) = reg.get(name) else { return 0.0 };
+    fam.series.iter()
+        .filter(|(labels, _)| filter.iter().all(|(k, v)| labels.iter().any(|(lk, lv)| lk == k && lv == v)))
+        .map(|(_, s)| s.value)
+        .sum()
+}
+
+fn escape(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn label_str(service: &str, labels: &[(String, String)], extra: Option<(&str, &str)>) -> String {
+    let mut parts = vec![format!("service=\"{}\"", escape(service))];
+    parts.extend(labels.iter().map(|(k, v)| format!("{}=\"{}\"", k, escape(v))));
+    if let Some((k, v)) = extra { parts.push(format!("{}=\"{}\"", k, v)); }
+    format!("{{{}}}", parts.join(","))
+}
+
+fn num(v: f64) -> String {
+    if v.is_infinite() { return if v > 0.0 { "+Inf".into() } else { "-Inf".into() }; }
+    format!("{}", v)
+}
+
+// The registry in Prometheus text exposition format 0.0.4.
+pub fn render() -> String {
+    let service = SERVICE.get().copied().unwrap_or("unknown");
+    let reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let mut out = String::new();
+    for (name, fam) in reg.iter() {
+        let _ = writeln!(out, "# HELP {} {}", name, fam.help);
+        let _ = writeln!(out, "# TYPE {} {}", name, fam.kind.as_str());
+        for (labels, s) in &fam.series {
+            if fam.kind != Kind::Histogram {
+                let _ = writeln!(out, "{}{} {}", name, label_str(service, labels, None), num(s.value));
+                continue;
+            }
+            let mut cumulative = 0u64;
+            for (b, n) in fam.bounds.iter().zip(&s.buckets) {
+                cumulative += n;
+                let _ = writeln!(out, "{}_bucket{} {}", name, label_str(service, labels, Some(("le", &num(*b)))), cumulative);
+            }
+            let _ = writeln!(out, "{}_bucket{} {}", name, label_str(service, labels, Some(("le", "+Inf"))), s.count);
+            let _ = writeln!(out, "{}_sum{} {}", name, label_str(service, labels, None), num(s.value));
+            let _ = writeln!(out, "{}_count{} {}", name, label_str(service, labels, None), s.count);
+        }
+    }
+    out
+}
+
+pub async fn handler() -> Response {
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], render()).into_response()
+}
+
+// Counts and times every request by method, matched route template (so /outlets/:id is one series,
+// not one per id) and status. Requests that match no route share route="unmatched".
+async fn track(req: Request, next: Next) -> Response {
+    let method = req.method().as_str().to_string();
+    let route = req.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string()).unwrap_or_else(|| "unmatched".into());
+    let started = Instant::now();
+    let resp = next.run(req).await;
+    let status = resp.status().as_u16().to_string();
+    counter_add("press_http_requests_total", "HTTP requests by method, route and status.",
+        &[("method", &method), ("route", &route), ("status", &status)], 1.0);
+    observe("press_http_request_duration_seconds", "HTTP request latency by method and route.", HTTP_BUCKETS,
+        &[("method", &method), ("route", &route)], started.elapsed().as_secs_f64());
+    resp
+}
+
+pub trait RouterExt {
+    // Times every route registered so far and adds GET /metrics (which is not itself timed).
+    fn with_metrics(self, service: &'static str) -> Self;
+}
+
+impl<S> RouterExt for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn with_metrics(self, service: &'static str) -> Self {
+        let _ = SERVICE.set(service);
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+        gauge_set("press_process_start_time_seconds", "Unix time the service started.", &[], started);
+        self.layer(middleware::from_fn(track)).route("/metrics", get(handler))
+    }
+}
+
+// --- HTTP totals ---
+
+// (requests, 5xx responses) since start, for services that also report them as JSON.
+pub fn http_totals() -> (u64, u64) {
+    let all = counter_sum("press_http_requests_total", &[]);
+    let reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let errors: f64 = reg.get("press_http_requests_total").map(|fam| fam.series.iter()
+        .filter(|(labels, _)| labels.iter().any(|(k, v)| k == "status" && v.starts_with('5')))
+        .map(|(_, s)| s.value)
+        .sum()).unwrap_or(0.0);
+    (all as u64, errors as u64)
+}
+
+// --- indexer ---
+
+pub fn indexer_blocks(stream: &str, blocks: u64) {
+    counter_add("press_indexer_blocks_processed_total", "Blocks read by eth_getLogs per stream.", &[("stream", stream)], blocks as f64);
+}
+
+pub fn indexer_position(stream: &str, head: i64, cursor: i64) {
+    gauge_set("press_indexer_head_block", "Chain head last seen per stream.", &[("stream", stream)], head as f64);
+    gauge_set("press_indexer_cursor_block", "Last block processed per stream.", &[("stream", stream)], cursor as f64);
+    gauge_set("press_indexer_lag_blocks", "Blocks between the chain head and the stream cursor.", &[("stream", stream)], (head - cursor).max(0) as f64);
+}
+
+// `kind` is "limit" (provider range/result limit, window shrunk), "rpc" or "head".
+pub fn indexer_error(stream: &str, kind: &str) {
+    counter_add("press_indexer_getlogs_errors_total", "Failed log or head requests per stream and kind.", &[("stream", stream), ("kind", kind)], 1.0);
+}
+
+// --- bots ---
+
+pub fn bot_message(platform: &str, ok: bool) {
+    if ok {
+        counter_add("press_bot_messages_sent_total", "Bot messages delivered per platform.", &[("platform", platform)], 1.0);
+    } else {
+        counter_add("press_bot_messages_failed_total", "Bot messages that failed to send per platform.", &[("platform", platform)], 1.0);
+    }
+}
+
+// --- oracle ---
+
+// `result` is the outcome the oracle reports (a risk level, "ok", "error", "rate_limited", ...).
+pub fn oracle_analysis(kind: &str, result: &str) {
+    counter_add("press_oracle_analyses_total", "Oracle analyses per kind and result.", &[("kind", kind), ("result", result)], 1.0);
+}
+
+// `resource` is "calls" or "tokens"; `limit` 0 means unlimited.
+pub fn oracle_budget(resource: &str, used: u64, limit: u64) {
+    gauge_set("press_oracle_budget_used", "Daily oracle budget used per resource.", &[("resource", resource)], used as f64);
+    gauge_set("press_oracle_budget_limit", "Daily oracle budget limit per resource (0 = unlimited).", &[("resource", resource)], limit as f64);
+}
+
+pub fn oracle_budget_denied() {
+    counter_add("press_oracle_budget_denied_total", "Oracle requests refused because the daily budget was spent.", &[], 1.0);
+}
+
+// --- deployer ---
+
+pub fn deployer_step(step: &str, result: &str, secs: f64) {
+    observe("press_deployer_step_duration_seconds", "Deployer step duration per step and result.", STEP_BUCKETS,
+        &[("step", step), ("result", result)], secs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The registry is process-global and tests run in parallel, so each test uses its own names.
+    fn lines(name: &str) -> Vec<String> {
+        render().lines()
+            .filter(|l| l.trim_start_matches("# HELP ").trim_start_matches("# TYPE ").starts_with(name))
+            .map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn counter_renders_help_type_and_summed_value() {
+        counter_add("test_counter_total", "A counter.", &[("kind", "a")], 1.0);
+        counter_add("test_counter_total", "A counter.", &[("kind", "a")], 2.0);
+        counter_add("test_counter_total", "A counter.", &[("kind", "a")], -5.0);
+        assert_eq!(lines("test_counter_total"), [
+            "# HELP test_counter_total A counter.",
+            "# TYPE test_counter_total counter",
+            "test_counter_total{service=\"unknown\",kind=\"a\"} 3",
+        ]);
+    }
+
+    #[test]
+    fn gauge_keeps_the_last_value() {
+        gauge_set("test_gauge", "A gauge.", &[], 7.0);
+        gauge_set("test_gauge", "A gauge.", &[], 2.5);
+        assert_eq!(lines("test_gauge"), [
+            "# HELP test_gauge A gauge.",
+            "# TYPE test_gauge gauge",
+            "test_gauge{service=\"unknown\"} 2.5",
+        ]);
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative_with_sum_and_count() {
+        const B: &[f64] = &[0.5, 1.0, 5.0];
+        for v in [0.25, 0.75, 1.0, 3.0, 10.0] {
+            observe("test_histogram_seconds", "A histogram.", B, &[("route", "/x")], v);
+        }
+        assert_eq!(lines("test_histogram_seconds"), [
+            "# HELP test_histogram_seconds A histogram.",
+            "# TYPE test_histogram_seconds histogram",
+            "test_histogram_seconds_bucket{service=\"unknown\",route=\"/x\",le=\"0.5\"} 1",
+            "test_histogram_seconds_bucket{service=\"unknown\",route=\"/x\",le=\"1\"} 3",
+            "test_histogram_seconds_bucket{service=\"unknown\",route=\"/x\",le=\"5\"} 4",
+            "test_histogram_seconds_bucket{service=\"unknown\",route=\"/x\",le=\"+Inf\"} 5",
+            "test_histogram_seconds_sum{service=\"unknown\",route=\"/x\"} 15",
+            "test_histogram_seconds_count{service=\"unknown\",route=\"/x\"} 5",
+        ]);
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        counter_add("test_escaped_total", "Escaping.", &[("v", "a\\b\"c\nd")], 1.0);
+        assert_eq!(lines("test_escaped_total").last().unwrap(), "test_escaped_total{service=\"unknown\",v=\"a\\\\b\\\"c\\nd\"} 1");
+    }
+}
@@ -0,0 +1,225 @@
+// Versioned SQLite / Postgres migrations shared by the Rust services.
+//
+// Each service embeds its numbered files (`migrations/NNN_name.sql`, the same layout as
+// ops/db/migrations) and calls `run` before serving. Pools are sqlx Any pools, so the same runner
+// serves both backends; a service that supports both keeps one file set per backend. Applied migrations are recorded in
+// schema_migrations with a SHA-256 of their SQL. A service refuses to start when the DB has drifted
+// from the binary: an applied file was edited, the DB knows a version the binary does not (it was
+// migrated by a newer build), or a version was skipped. Every migration runs in its own transaction
+// together with its schema_migrations row, so a failed file leaves nothing half-applied.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{AnyPool, Executor, Row};
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Applied {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Pending {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Status {
+    // highest applied version; 0 for an empty DB
+    pub version: i64,
+    // highest version this binary knows
+    pub latest: i64,
+    pub applied: Vec<Applied>,
+    pub pending: Vec<Pending>,
+    pub drift: Vec<String>,
+}
+
+impl Status {
+    pub fn ok(&self) -> bool { self.drift.is_empty() && self.pending.is_empty() }
+}
+
+// Line endings are normalised so a checkout with CRLF does not count as an edit.
+pub fn checksum(sql: &str) -> String {
+    hex::encode(Sha256::digest(sql.replace("\r\n", "\n").as_bytes()))
+}
+
+// Opens a pool for a sqlite: or postgres:// URL.
+pub async fn connect(url: &str) -> Result<AnyPool, String> {
+    sqlx::any::install_default_drivers();
+    AnyPool::connect(url).await.map_err(|e| e.to_string())
+}
+
+pub fn is_postgres(db: &AnyPool) -> bool {
+    db.connect_options().database_url.scheme().starts_with("postgres")
+}
+
+async fn ensure_table(db: &AnyPool) -> Result<(), String> {
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        );
+    "#).execute(db).await.map(|_| ()).map_err(|e| format!("schema_migrations: {}", e))
+}
+
+// True for a DB that was set up before schema_migrations existed: it has tables but no record of
+// which version they correspond to. Services bring such DBs up to their baseline before `run`.
+pub async fn is_unversioned(db: &AnyPool) -> bool {
+    let sql = if is_postgres(db) {
+        "SELECT CAST(table_name AS TEXT) AS name FROM information_schema.tables WHERE table_schema = current_schema()"
+    } else {
+        "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%'"
+    };
+    let tables: Vec<String> = sqlx::query(sql)
+        .fetch_all(db).await.unwrap_or_default()
+        .iter().map(|r| r.get::<String,_>("name")).collect();
+    !tables.is_empty() && !tables.iter().any(|t| t == "schema_migrations")
+}
+
+async fn applied(db: &AnyPool) -> Vec<Applied> {
+    sqlx::query("SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version")
+        .fetch_all(db).await.unwrap_or_default()
+        .iter().map(|r| Applied {
+            version: r.get("version"),
+            name: r.get("name"),
+            checksum: r.get("checksum"),
+            applied_at: r.get("applied_at"),
+        }).collect()
+}
+
+fn check_order(migrations: &[Migration]) -> Result<(), String> {
+    for w in migrations.windows(2) {
+        if w[1].version <= w[0].version {
+            return Err(format!("migrations out of order: {:03} after {:03}", w[1].version, w[0].version));
+        }
+    }
+    Ok(())
+}
+
+pub async fn status(db: &AnyPool, migrations: &[Migration]) -> Status {
+    let done = applied(db).await;
+    let version = done.iter().map(|a| a.version).max().unwrap_or(0);
+    let mut drift = vec![];
+    if let Err(e) = check_order(migrations) { drift.push(e); }
+    for a in &done {
+        match migrations.iter().find(|m| m.version == a.version) {
+            None => drift.push(format!("{:03}_{} is applied but unknown to this build", a.version, a.name)),
+            Some(m) if checksum(m.sql) != a.checksum => drift.push(format!("{:03}_{} was changed after it was applied", a.version, a.name)),
+            Some(_) => {}
+        }
+    }
+    let mut pending = vec![];
+    for m in migrations.iter().filter(|m| !done.iter().any(|a| a.version == m.version)) {
+        if m.version < version {
+            drift.push(format!("{:03}_{} was skipped (DB is at {:03})", m.version, m.name, version));
+        } else {
+            pending.push(Pending { version: m.version, name: m.name.to_string(), checksum: checksum(m.sql) });
+        }
+    }
+    Status { version, latest: migrations.last().map(|m| m.version).unwrap_or(0), applied: done, pending, drift }
+}
+
+// Applies every pending migration in order. Fails without touching the DB when it has drifted.
+pub async fn run(db: &AnyPool, migrations: &[Migration]) -> Result<Status, String> {
+    ensure_table(db).await?;
+    let st = status(db, migrations).await;
+    if !st.drift.is_empty() {
+        return Err(format!("schema drift: {}", st.drift.join("; ")));
+    }
+    for p in &st.pending {
+        let m = migrations.iter().find(|m| m.version == p.version).expect("pending migration");
+        let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+        // unprepared, so a file may hold several statements on Postgres too
+        (&mut *tx).execute(m.sql).await
+            .map_err(|e| format!("{:03}_{}: {}", m.version, m.name, e))?;
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES ($1,$2,$3,$4)")
+            .bind(m.version).bind(m.name).bind(&p.checksum).bind(chrono::Utc::now().to_rfc3339())
+            .execute(&mut *tx).await.map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        eprintln!("migrations: applied {:03}_{}", m.version, m.name);
+    }
+    Ok(status(db, migrations).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::any::AnyPoolOptions;
+
+    // every connection to sqlite::memory: is its own DB, so keep to one
+    async fn memory() -> AnyPool {
+        sqlx::any::install_default_drivers();
+        AnyPoolOptions::new().max_connections(1).connect("sqlite::memory:").await.expect("sqlite::memory:")
+    }
+
+    fn m(version: i64, name: &'static str, sql: &'static str) -> Migration {
+        Migration { version, name, sql }
+    }
+
+    #[tokio::test]
+    async fn fresh_db_has_everything_pending() {
+        let db = memory().await;
+        ensure_table(&db).await.unwrap();
+        let st = status(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);"), m(2, "b", "CREATE TABLE b (x BIGINT);")]).await;
+        assert_eq!((st.version, st.latest), (0, 2));
+        assert_eq!(st.pending.iter().map(|p| p.version).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(st.drift.is_empty());
+    }
+
+    #[tokio::test]
+    async fn edited_file_is_drift() {
+        let db = memory().await;
+        run(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);")]).await.unwrap();
+        let st = status(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT, y TEXT);")]).await;
+        assert_eq!(st.drift, vec!["001_a was changed after it was applied"]);
+        assert!(run(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT, y TEXT);")]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn crlf_checkout_is_not_an_edit() {
+        let db = memory().await;
+        run(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);\nCREATE TABLE b (x BIGINT);\n")]).await.unwrap();
+        let st = status(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);\r\nCREATE TABLE b (x BIGINT);\r\n")]).await;
+        assert!(st.ok(), "{:?}", st.drift);
+    }
+
+    #[tokio::test]
+    async fn version_unknown_to_this_build_is_drift() {
+        let db = memory().await;
+        run(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);"), m(2, "b", "CREATE TABLE b (x BIGINT);")]).await.unwrap();
+        let st = status(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);")]).await;
+        assert_eq!((st.version, st.latest), (2, 1));
+        assert_eq!(st.drift, vec!["002_b is applied but unknown to this build"]);
+    }
+
+    #[tokio::test]
+    async fn skipped_version_is_drift_not_pending() {
+        let db = memory().await;
+        run(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);"), m(3, "c", "CREATE TABLE c (x BIGINT);")]).await.unwrap();
+        let st = status(&db, &[m(1, "a", "CREATE TABLE a (x BIGINT);"), m(2, "b", "CREATE TABLE b (x BIGINT);"), m(3, "c", "CREATE TABLE c (x BIGINT);")]).await;
+        assert_eq!(st.drift, vec!["002_b was skipped (DB is at 003)"]);
+        assert!(st.pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn out_of_order_versions_are_drift() {
+        let db = memory().await;
+        let migrations = [m(2, "b", "CREATE TABLE b (x BIGINT);"), m(1, "a", "CREATE TABLE a (x BIGINT);")];
+        assert!(run(&db, &migrations).await.is_err());
+        let st = status(&db, &migrations).await;
+        assert_eq!(st.drift, vec!["migrations out of order: 001 after 002"]);
+        assert_eq!(st.version, 0);
+    }
+}
@@ -2,6 +2,7 @@ use axum::{routing::{get, post}, Json, Router};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::net::SocketAddr;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct Health { ok: bool }
@@ -80,7 +81,8 @@ async fn main() -> anyhow::Result<()> {
     .route("/health", get(|| async {"ok"}))
     .route("/v1/health", get(health))
     .route("/v1/config", get(config))
-    .route("/v1/verify/article", post(verify));
+    .route("/v1/verify/article", post(verify))
+    .with_metrics("press_oracle");
   axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
   Ok(())
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use tokio::process::Command;
 use std::path::PathBuf;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct OkResp { ok: bool }
@@ -244,7 +245,8 @@ async fn main() -> anyhow::Result<()> {
     .route("/v1/outlet/token/deploy", post(token_deploy))
     .route("/v1/outlet/token/deploy/prepare", post(token_deploy_prepare))
     .route("/v1/outlet/token/deploy/verify", post(token_deploy_verify))
-    .route("/v1/outlet/token/test/verify", post(token_test_verify));
+    .route("/v1/outlet/token/test/verify", post(token_test_verify))
+    .with_metrics("press_outlet_api");
   axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
   Ok(())
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use rusqlite::{Connection, params};
 use std::{net::SocketAddr, sync::Arc};
 use tokio::sync::Mutex;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct Health { ok: bool }
@@ -71,6 +72,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/v1/pools", get(pools))
         .route("/v1/bounties", get(bounties))
         .route("/v1/claim", post(claim))
+        .with_metrics("press_rewards")
         .with_state(db);
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
     Ok(())
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use rusqlite::{Connection, params};
 use std::{net::SocketAddr, sync::Arc};
 use tokio::sync::Mutex;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct Health { ok: bool }
@@ -112,6 +113,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/v1/register", post(register))
         .route("/v1/requests", post(create_request))
         .route("/v1/decide", post(decide))
+        .with_metrics("press_sources")
         .with_state(db);
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
     Ok(())
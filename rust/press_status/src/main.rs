@@ -1,6 +1,7 @@
 use axum::{routing::get, Json, Router};
 use serde::Serialize;
 use std::net::SocketAddr;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct Probe { name: String, ok: bool, url: String }
@@ -31,7 +32,8 @@ async fn main() -> anyhow::Result<()> {
   let addr: SocketAddr = "0.0.0.0:8812".parse().unwrap();
   let app = Router::new()
     .route("/health", get(|| async {"ok"}))
-    .route("/v1/status", get(status));
+    .route("/v1/status", get(status))
+    .with_metrics("press_status");
   axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
   Ok(())
 }
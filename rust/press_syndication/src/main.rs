@@ -1,6 +1,7 @@
 use axum::{routing::get, Json, Router};
 use serde::Serialize;
 use std::net::SocketAddr;
+use press_metrics::RouterExt;
 
 #[derive(Serialize)]
 struct Status { ok: bool }
@@ -12,7 +13,8 @@ async fn main() -> anyhow::Result<()> {
     let addr: SocketAddr = "0.0.0.0:8810".parse().unwrap();
     let app = Router::new()
         .route("/health", get(|| async { "ok" }))
-        .route("/v1/status", get(status));
+        .route("/v1/status", get(status))
+        .with_metrics("press_syndication");
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
     Ok(())
 }
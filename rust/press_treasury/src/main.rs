@@ -1,17 +1,73 @@
-use axum::{routing::get, Json, Router};
+use axum::{extract::RawQuery, routing::get, Json, Router};
 use serde::Serialize;
 use std::net::SocketAddr;
+use press_metrics::RouterExt;
+
+// Balances and fee flows come from the indexer's treasury ledger (/treasury/*); this service adds the
+// flywheel config on top.
+
+fn flywheel() -> serde_json::Value {
+    serde_json::from_str(include_str!("../../../config/treasury_flywheel.json")).unwrap_or_default()
+}
+
+fn indexer_api() -> String {
+    std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into())
+}
+
+async fn indexer_get(path: &str, raw: Option<String>) -> Option<serde_json::Value> {
+    let url = match raw.filter(|q| !q.is_empty()) {
+        Some(qs) => format!("{}{}?{}", indexer_api(), path, qs),
+        None => format!("{}{}", indexer_api(), path),
+    };
+    reqwest::Client::new().get(url).send().await.ok()?.json::<serde_json::Value>().await.ok()
+}
 
 #[derive(Serialize)]
 struct Status {
     ok: bool,
-    treasury_balance_press: u64,
-    next_burn_epoch: String
+    // raw PRESS units (18 decimals); a string so it does not overflow
+    treasury_balance_press: String,
+    burned_press: String,
+    next_burn_epoch: String,
+    indexer: bool,
 }
 
 async fn status() -> Json<Status> {
-    // Production: query on-chain treasury balance + schedule
-    Json(Status{ ok:true, treasury_balance_press: 0, next_burn_epoch: "yearly".into() })
+    let cfg = flywheel();
+    let epoch = cfg.get("buyback_burn").and_then(|b| b.get("epoch")).and_then(|e| e.as_str()).unwrap_or("yearly").to_string();
+    let summary = indexer_get("/treasury/summary", Some("asset=PRESS".into())).await;
+    let balance = |account: &str| -> String {
+        summary.as_ref()
+            .and_then(|s| s.get("balances")).and_then(|b| b.as_array())
+            .and_then(|b| b.iter().find(|x| x.get("account").and_then(|a| a.as_str()) == Some(account) && x.get("asset").and_then(|a| a.as_str()) == Some("PRESS")))
+            .and_then(|x| x.get("balance")).and_then(|v| v.as_str())
+            .unwrap_or("0").to_string()
+    };
+    Json(Status {
+        ok: true,
+        treasury_balance_press: balance("treasury"),
+        burned_press: balance("burn"),
+        next_burn_epoch: epoch,
+        indexer: summary.is_some(),
+    })
+}
+
+// Inflows by fee source, outflows and running totals per epoch (?epoch=yearly|monthly|weekly|daily,
+// defaulting to the flywheel's burn epoch; ?asset=, ?units=human are passed through).
+async fn flows(RawQuery(raw): RawQuery) -> Json<serde_json::Value> {
+    match indexer_get("/treasury/summary", raw).await {
+        Some(mut j) => {
+            j["flywheel"] = flywheel();
+            Json(j)
+        }
+        None => Json(serde_json::json!({"ok": false, "error": "indexer unavailable", "epochs": []})),
+    }
+}
+
+// Ledger legs, newest first (?account=, ?category=, ?asset=, ?before=, ?limit=).
+async fn ledger(RawQuery(raw): RawQuery) -> Json<serde_json::Value> {
+    Json(indexer_get("/treasury/ledger", raw).await
+        .unwrap_or_else(|| serde_json::json!({"ok": false, "error": "indexer unavailable", "items": []})))
 }
 
 #[tokio::main]
@@ -19,7 +75,10 @@ async fn main() -> anyhow::Result<()> {
     let addr: SocketAddr = "0.0.0.0:8807".parse().unwrap();
     let app = Router::new()
         .route("/health", get(|| async { "ok" }))
-        .route("/v1/status", get(status));
+        .route("/v1/status", get(status))
+        .route("/v1/flows", get(flows))
+        .route("/v1/ledger", get(ledger))
+        .with_metrics("press_treasury");
     axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
     Ok(())
 }
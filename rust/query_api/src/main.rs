@@ -3,10 +3,14 @@ use serde::Serialize;
 use sqlx::PgPool;
 use std::net::SocketAddr;
 use tower_http::cors::CorsLayer;
+use press_metrics::RouterExt;
+
+mod roles;
 
 #[derive(Clone)]
 struct AppState {
     db: PgPool,
+    role_store: roles::RoleStore,
 }
 
 #[derive(Serialize)]
@@ -40,16 +44,18 @@ struct MetricRow {
     updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+// outlets and outlet_tokens are written by press-indexer (rust/indexer/migrations/postgres); the
+// old created_at / token_address / deployed_at names are kept as aliases.
 async fn outlets(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
     let rows = sqlx::query!(
-        r#"SELECT outlet_id, owner, name, domain, created_at FROM outlets ORDER BY created_at DESC LIMIT 200"#
+        r#"SELECT outlet_id, owner, name, domain, bond_paid, fee_paid, block_number, tx_hash, finalized, inserted_at AS created_at FROM outlets ORDER BY block_number DESC, log_index DESC LIMIT 200"#
     ).fetch_all(&st.db).await.unwrap_or_default();
     Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
 }
 
 async fn outlet_tokens(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
     let rows = sqlx::query!(
-        r#"SELECT token_address, owner, name, symbol, supply, deployed_at FROM outlet_tokens ORDER BY deployed_at DESC LIMIT 200"#
+        r#"SELECT token AS token_address, outlet_id, owner, name, symbol, supply, fee_paid, block_number, tx_hash, finalized, inserted_at AS deployed_at FROM outlet_tokens ORDER BY block_number DESC, log_index DESC LIMIT 200"#
     ).fetch_all(&st.db).await.unwrap_or_default();
     Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
 }
@@ -61,11 +67,20 @@ async fn articles(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
     Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
 }
 
-async fn proposals(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
-    let rows = sqlx::query!(
-        r#"SELECT proposal_id, proposer, proposal_type, title, description_uri, created_at, ends_at, fee_paid FROM proposals ORDER BY created_at DESC LIMIT 200"#
-    ).fetch_all(&st.db).await.unwrap_or_default();
-    Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
+// Proposals and votes are indexed by press-indexer (PressGovernance VoteCast plus
+// PressGovernanceSignals ProposalVoted); nothing writes the ops proposals / proposal_votes tables.
+async fn indexer_json(path: &str) -> Option<serde_json::Value> {
+    let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
+    let r = reqwest::Client::new().get(format!("{}{}", idx, path)).send().await.ok()?;
+    r.json::<serde_json::Value>().await.ok()
+}
+
+fn items_of(j: Option<serde_json::Value>) -> Vec<serde_json::Value> {
+    j.and_then(|j| j.get("items").and_then(|v| v.as_array()).cloned()).unwrap_or_default()
+}
+
+async fn proposals() -> Json<Vec<serde_json::Value>> {
+    Json(items_of(indexer_json("/governance/proposals?limit=200").await))
 }
 
 async fn params(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
@@ -80,16 +95,15 @@ async fn bonds(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
     Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
 }
 
-async fn proposal_votes(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
-    let rows = sqlx::query!(r#"SELECT proposal_id, voter, support, weight, created_at FROM proposal_votes ORDER BY created_at DESC LIMIT 500"#)
-        .fetch_all(&st.db).await.unwrap_or_default();
-    Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
+async fn proposal_votes() -> Json<Vec<serde_json::Value>> {
+    Json(items_of(indexer_json("/governance/votes?limit=500").await))
 }
 
 async fn governance_overview(State(st): State<AppState>) -> Json<serde_json::Value> {
-    let proposals = sqlx::query!(r#"SELECT COUNT(*)::BIGINT as n FROM proposals"#).fetch_one(&st.db).await.ok().and_then(|r| r.n).unwrap_or(0);
+    let total = |j: Option<serde_json::Value>| j.and_then(|j| j.get("total").and_then(|v| v.as_i64())).unwrap_or(0);
+    let proposals = total(indexer_json("/governance/proposals?limit=1").await);
     let active_council = sqlx::query!(r#"SELECT COUNT(*)::BIGINT as n FROM council_members WHERE active=true"#).fetch_one(&st.db).await.ok().and_then(|r| r.n).unwrap_or(0);
-    let votes = sqlx::query!(r#"SELECT COUNT(*)::BIGINT as n FROM proposal_votes"#).fetch_one(&st.db).await.ok().and_then(|r| r.n).unwrap_or(0);
+    let votes = total(indexer_json("/governance/votes?limit=1").await);
     let params = sqlx::query!(r#"SELECT COUNT(*)::BIGINT as n FROM params"#).fetch_one(&st.db).await.ok().and_then(|r| r.n).unwrap_or(0);
 
     Json(serde_json::json!({
@@ -124,11 +138,27 @@ async fn multisig_txs(State(st): State<AppState>) -> Json<Vec<serde_json::Value>
     Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
 }
 
-async fn court_cases(State(st): State<AppState>) -> Json<Vec<serde_json::Value>> {
-    let rows = sqlx::query!(
-        r#"SELECT case_id, outlet_id, filed_by, case_type, evidence_uri, created_at, status FROM court_cases ORDER BY created_at DESC LIMIT 200"#
-    ).fetch_all(&st.db).await.unwrap_or_default();
-    Json(rows.into_iter().map(|r| serde_json::json!(r)).collect())
+// Court cases are indexed by press-indexer from Court.sol; the shape (a plain array) is unchanged.
+async fn court_cases() -> Json<Vec<serde_json::Value>> {
+    let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
+    if let Ok(r) = reqwest::Client::new().get(format!("{}/court/cases/latest", idx)).send().await {
+        if let Ok(j) = r.json::<serde_json::Value>().await {
+            return Json(j.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default());
+        }
+    }
+    Json(vec![])
+}
+
+async fn court_case_timeline(axum::extract::Path(case_id): axum::extract::Path<String>, axum::extract::RawQuery(raw): axum::extract::RawQuery) -> Json<serde_json::Value> {
+    let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
+    let mut url = format!("{}/court/cases/{}/timeline", idx, urlencoding::encode(&case_id));
+    if let Some(qs) = raw.filter(|q| !q.is_empty()) { url.push_str(&format!("?{}", qs)); }
+    if let Ok(r) = reqwest::Client::new().get(url).send().await {
+        if let Ok(j) = r.json::<serde_json::Value>().await {
+            return Json(j);
+        }
+    }
+    Json(serde_json::json!({"ok": false, "case_id": case_id, "error": "indexer unavailable", "timeline": []}))
 }
 
 async fn metrics(State(st): State<AppState>) -> Json<Vec<MetricRow>> {
@@ -148,7 +178,8 @@ async fn main() {
     let port: u16 = std::env::var("PORT").ok().and_then(|s| s.parse().ok()).unwrap_or(8787);
 
     let db = PgPool::connect(&db_url).await.expect("db connect");
-    let st = AppState { db };
+    let st = AppState { db, role_store: roles::RoleStore::new() };
+    tokio::spawn(roles::invalidate_loop(st.role_store.clone()));
 
     
 #[derive(serde::Deserialize)]
@@ -252,7 +283,7 @@ struct CouncilPolicySetReq {
 
 async fn admin_set_council_policy(State(state): State<AppState>, headers: axum::http::HeaderMap, Json(req): Json<CouncilPolicySetReq>) -> Result<Json<serde_json::Value>, StatusCode> {
     if !is_admin_headers(&state, &headers) { return Err(StatusCode::UNAUTHORIZED); }
-    let _ = sqlx::query("UPDATE council_policy SET term_days=?1, min_actions_30d=?2, bond_min_wei=?3 WHERE id=1")
+    let _ = sqlx::query("UPDATE council_policy SET term_days=$1, min_actions_30d=$2, bond_min_wei=$3 WHERE id=1")
         .bind(req.term_days).bind(req.min_actions_30d).bind(req.bond_min_wei).execute(&state.db).await;
     Ok(Json(serde_json::json!({"ok": true})))
 }
@@ -265,8 +296,8 @@ struct CouncilTermSetReq { wallet: String, term_start: i64 }
 
 async fn admin_set_council_term(State(state): State<AppState>, headers: axum::http::HeaderMap, Json(req): Json<CouncilTermSetReq>) -> Result<Json<serde_json::Value>, StatusCode> {
     if !is_admin_headers(&state, &headers) { return Err(StatusCode::UNAUTHORIZED); }
-    let _ = sqlx::query("INSERT INTO council_terms(wallet,term_start) VALUES (?1,?2) ON CONFLICT(wallet) DO UPDATE SET term_start=excluded.term_start")
-        .bind(req.wallet).bind(req.term_start).execute(&state.db).await;
+    let _ = sqlx::query("INSERT INTO council_terms(wallet,term_start) VALUES ($1,$2) ON CONFLICT(wallet) DO UPDATE SET term_start=excluded.term_start")
+        .bind(req.wallet.trim().to_lowercase()).bind(req.term_start).execute(&state.db).await;
     Ok(Json(serde_json::json!({"ok": true})))
 }
 
@@ -275,8 +306,8 @@ struct CouncilActivitySetReq { wallet: String, actions_30d: i64, last_action: i6
 
 async fn admin_set_council_activity(State(state): State<AppState>, headers: axum::http::HeaderMap, Json(req): Json<CouncilActivitySetReq>) -> Result<Json<serde_json::Value>, StatusCode> {
     if !is_admin_headers(&state, &headers) { return Err(StatusCode::UNAUTHORIZED); }
-    let _ = sqlx::query("INSERT INTO council_activity(wallet,actions_30d,last_action) VALUES (?1,?2,?3) ON CONFLICT(wallet) DO UPDATE SET actions_30d=excluded.actions_30d, last_action=excluded.last_action")
-        .bind(req.wallet).bind(req.actions_30d).bind(req.last_action).execute(&state.db).await;
+    let _ = sqlx::query("INSERT INTO council_activity(wallet,actions_30d,last_action) VALUES ($1,$2,$3) ON CONFLICT(wallet) DO UPDATE SET actions_30d=excluded.actions_30d, last_action=excluded.last_action")
+        .bind(req.wallet.trim().to_lowercase()).bind(req.actions_30d).bind(req.last_action).execute(&state.db).await;
     Ok(Json(serde_json::json!({"ok": true})))
 }
 
@@ -286,8 +317,8 @@ struct CouncilBondSetReq { wallet: String, bond_wei: String }
 async fn admin_set_council_bond(State(state): State<AppState>, headers: axum::http::HeaderMap, Json(req): Json<CouncilBondSetReq>) -> Result<Json<serde_json::Value>, StatusCode> {
     if !is_admin_headers(&state, &headers) { return Err(StatusCode::UNAUTHORIZED); }
     let now = now_ts();
-    let _ = sqlx::query("INSERT INTO council_bonds(wallet,bond_wei,updated_at) VALUES (?1,?2,?3) ON CONFLICT(wallet) DO UPDATE SET bond_wei=excluded.bond_wei, updated_at=excluded.updated_at")
-        .bind(req.wallet).bind(req.bond_wei).bind(now).execute(&state.db).await;
+    let _ = sqlx::query("INSERT INTO council_bonds(wallet,bond_wei,updated_at) VALUES ($1,$2,$3) ON CONFLICT(wallet) DO UPDATE SET bond_wei=excluded.bond_wei, updated_at=excluded.updated_at")
+        .bind(req.wallet.trim().to_lowercase()).bind(req.bond_wei).bind(now).execute(&state.db).await;
     Ok(Json(serde_json::json!({"ok": true})))
 }
 
@@ -321,16 +352,71 @@ struct OutletPoolStatus {
 }
 
 async fn outlet_pool_status(State(state): State<AppState>, axum::extract::Query(q): axum::extract::Query<OutletQ>) -> Json<OutletPoolStatus> {
-    let bal = sqlx::query("SELECT balance_wei FROM outlet_pool WHERE outlet_id=?1")
+    let bal = sqlx::query("SELECT balance_wei FROM outlet_pool WHERE outlet_id=$1")
         .bind(&q.outlet_id).fetch_optional(&state.db).await.ok().flatten()
         .map(|r| r.get::<String,_>(0)).unwrap_or("0".into());
-    let members = sqlx::query("SELECT COUNT(*) FROM outlet_members WHERE outlet_id=?1")
+    let members = sqlx::query("SELECT COUNT(*) FROM outlet_members WHERE outlet_id=$1")
         .bind(&q.outlet_id).fetch_one(&state.db).await.ok().map(|r| r.get::<i64,_>(0) as usize).unwrap_or(0);
     Json(OutletPoolStatus{ outlet_id: q.outlet_id, balance_wei: bal, members })
 }
 
+// Refreshes council_bonds / council_terms / council_activity for `wallet` from the indexer's
+// BondVault and CouncilRegistry state. Rows are left as they are when the indexer is unreachable, so
+// the admin setters still work as a manual fallback.
+async fn sync_council_from_indexer(state: &AppState, wallet: &str) {
+    let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
+    let url = format!("{}/council/wallet/{}", idx, urlencoding::encode(wallet));
+    let Some(j) = (match reqwest::Client::new().get(url).send().await {
+        Ok(r) => r.json::<serde_json::Value>().await.ok(),
+        Err(_) => None,
+    }) else { return };
+    if j.get("ok").and_then(|v| v.as_bool()) != Some(true) { return; }
+    let now = now_ts();
+
+    let bond_wei = j.get("bond_wei").and_then(|v| v.as_str()).unwrap_or("0").to_string();
+    if let Err(e) = sqlx::query("INSERT INTO council_bonds(wallet,bond_wei,updated_at) VALUES ($1,$2,$3) ON CONFLICT(wallet) DO UPDATE SET bond_wei=excluded.bond_wei, updated_at=excluded.updated_at")
+        .bind(wallet).bind(bond_wei).bind(now).execute(&state.db).await {
+        eprintln!("council sync: council_bonds {wallet}: {e}");
+    }
+
+    let actions_30d = j.get("actions_30d").and_then(|v| v.as_i64()).unwrap_or(0);
+    let last_action = j.get("last_action").and_then(|v| v.as_i64()).unwrap_or(0);
+    if let Err(e) = sqlx::query("INSERT INTO council_activity(wallet,actions_30d,last_action) VALUES ($1,$2,$3) ON CONFLICT(wallet) DO UPDATE SET actions_30d=excluded.actions_30d, last_action=excluded.last_action")
+        .bind(wallet).bind(actions_30d).bind(last_action).execute(&state.db).await {
+        eprintln!("council sync: council_activity {wallet}: {e}");
+    }
+
+    let term = j.get("term").filter(|t| t.get("active").and_then(|a| a.as_bool()) == Some(true));
+    if let Some(term_start) = term.and_then(|t| t.get("term_start")).and_then(|v| v.as_i64()) {
+        if let Err(e) = sqlx::query("INSERT INTO council_terms(wallet,term_start) VALUES ($1,$2) ON CONFLICT(wallet) DO UPDATE SET term_start=excluded.term_start")
+            .bind(wallet).bind(term_start).execute(&state.db).await {
+            eprintln!("council sync: council_terms {wallet}: {e}");
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RolesOfQ { wallet: String, outlet_id: Option<String> }
+
+// `roles` stays the flat list existing callers match on; `global` and `outlets` carry the scope.
+// With ?outlet_id= the flat list is narrowed to global roles plus that outlet's roles.
+async fn roles_of(State(state): State<AppState>, axum::extract::Query(q): axum::extract::Query<RolesOfQ>) -> Json<serde_json::Value> {
+    match state.role_store.resolve(&q.wallet).await {
+        Ok(set) => {
+            let roles = match q.outlet_id.as_deref() {
+                Some(o) => set.global.iter().chain(set.outlets.get(o).into_iter().flatten()).cloned().collect(),
+                None => set.all(),
+            };
+            Json(serde_json::json!({"ok": true, "wallet": set.wallet, "roles": roles, "global": set.global, "outlets": set.outlets}))
+        }
+        Err(e) => Json(serde_json::json!({"ok": false, "wallet": q.wallet, "error": e, "roles": []})),
+    }
+}
+
 async fn council_eligible(State(state): State<AppState>, axum::extract::Query(q): axum::extract::Query<CouncilEligibleQ>) -> Json<CouncilEligibleResp> {
-    let wallet = q.wallet.clone();
+    // council tables are keyed by the lowercase address, as the indexer and role cache report it
+    let wallet = q.wallet.trim().to_lowercase();
+    sync_council_from_indexer(&state, &wallet).await;
 
     // Base: wallet must have COUNCIL role on-chain
     let roles = state.role_store.roles_of(&wallet).await.unwrap_or_default();
@@ -348,18 +434,18 @@ async fn council_eligible(State(state): State<AppState>, axum::extract::Query(q)
     // Term: if no term_start exists yet but has_role, auto-set term_start = now (MVP convenience).
     let mut term_ok = false;
     if has_role {
-        let term_row = sqlx::query("SELECT term_start FROM council_terms WHERE wallet=?1")
+        let term_row = sqlx::query("SELECT term_start FROM council_terms WHERE wallet=$1")
             .bind(&wallet).fetch_optional(&state.db).await.ok().flatten();
         let term_start:i64 = if let Some(r)=term_row { r.get(0) } else {
-            let _ = sqlx::query("INSERT INTO council_terms(wallet,term_start) VALUES (?1,?2)")
+            let _ = sqlx::query("INSERT INTO council_terms(wallet,term_start) VALUES ($1,$2)")
                 .bind(&wallet).bind(now).execute(&state.db).await;
             now
         };
         term_ok = now <= term_start + (term_days * 86400);
     }
 
-    // Activity: expects indexer to maintain actions_30d. If missing and has_role, treat as not ok (forces real activity tracking).
-    let act_row = sqlx::query("SELECT actions_30d, last_action FROM council_activity WHERE wallet=?1")
+    // Activity: maintained from the indexer (sync_council_from_indexer). If missing and has_role, treat as not ok (forces real activity tracking).
+    let act_row = sqlx::query("SELECT actions_30d, last_action FROM council_activity WHERE wallet=$1")
         .bind(&wallet).fetch_optional(&state.db).await.ok().flatten();
     let mut activity_ok = false;
     let mut last_action = 0i64;
@@ -372,8 +458,8 @@ async fn council_eligible(State(state): State<AppState>, axum::extract::Query(q)
         activity_ok = false;
     }
 
-    // Bond: expects indexer/contract read to maintain bond_wei. If missing, treat as not ok when policy requires >0.
-    let bond_row = sqlx::query("SELECT bond_wei FROM council_bonds WHERE wallet=?1")
+    // Bond: maintained from the indexer (sync_council_from_indexer). If missing, treat as not ok when policy requires >0.
+    let bond_row = sqlx::query("SELECT bond_wei FROM council_bonds WHERE wallet=$1")
         .bind(&wallet).fetch_optional(&state.db).await.ok().flatten();
     let mut bond_ok = false;
     let mut bond_wei = "0".to_string();
@@ -408,9 +494,11 @@ async fn council_eligible(State(state): State<AppState>, axum::extract::Query(q)
 let app = Router::new()
         .route("/outlet/pool/status", get(outlet_pool_status))
         .route("/council/eligible", get(council_eligible))
+        .route("/roles/of", get(roles_of))
         .route("/health", get(health))
         .route("/api/search", get(search))
         .route("/api/feed/:feed", get(feed))
+        .route("/api/feed/:feed/stream", get(feed_stream))
         .route("/v1/contracts", get(contracts))
         .route("/v1/metrics", get(metrics))
         .route("/v1/outlets", get(outlets))
@@ -418,6 +506,7 @@ let app = Router::new()
         .route("/v1/articles", get(articles))
         .route("/v1/proposals", get(proposals))
         .route("/v1/court_cases", get(court_cases))
+        .route("/v1/court_cases/:case_id/timeline", get(court_case_timeline))
         .route("/v1/params", get(params))
         .route("/v1/bonds", get(bonds))
         .route("/v1/proposal_votes", get(proposal_votes))
@@ -426,6 +515,7 @@ let app = Router::new()
         .route("/v1/council_members", get(council_members))
         .route("/v1/council_votes", get(council_votes))
         .route("/v1/governance_overview", get(governance_overview))
+        .with_metrics("query_api")
         .layer(CorsLayer::permissive())
         .with_state(st);
 
@@ -441,30 +531,44 @@ let app = Router::new()
 use axum::extract::{Query, Path};
 
 #[derive(Deserialize)]
-struct SearchQ { q: String }
+struct SearchQ {
+    q: String,
+    outlet: Option<String>,
+    event: Option<String>,
+    kind: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<i64>,
+}
 
 async fn search(Query(q): Query<SearchQ>) -> Json<serde_json::Value> {
     let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
-    let url = format!("{}/search?q={}", idx, urlencoding::encode(&q.q));
+    let mut url = format!("{}/search?q={}", idx, urlencoding::encode(&q.q));
+    if let Some(o) = q.outlet.as_ref() { url.push_str(&format!("&outlet={}", urlencoding::encode(o))); }
+    if let Some(e) = q.event.as_ref() { url.push_str(&format!("&event={}", urlencoding::encode(e))); }
+    if let Some(k) = q.kind.as_ref() { url.push_str(&format!("&kind={}", urlencoding::encode(k))); }
+    if let Some(f) = q.from { url.push_str(&format!("&from={}", f)); }
+    if let Some(t) = q.to { url.push_str(&format!("&to={}", t)); }
+    if let Some(l) = q.limit { url.push_str(&format!("&limit={}", l)); }
     if let Ok(r) = reqwest::Client::new().get(url).send().await {
         if let Ok(j) = r.json::<serde_json::Value>().await {
             return Json(j);
         }
     }
-    Json(serde_json::json!({"ok": true, "query": q.q, "items": []}))
-}
-
-    // Placeholder: return empty list with echo; real implementation queries indexer db.
-    Json(serde_json::json!({"ok": true, "query": q.q, "items": []}))
+    Json(serde_json::json!({"ok": false, "query": q.q, "error": "indexer unavailable", "items": []}))
 }
 
 #[derive(Deserialize)]
-struct FeedQ { after: Option<i64>, outlet: Option<String>, article_id: Option<String>, kind: Option<String>, severity: Option<i64> }
+struct FeedQ { after: Option<i64>, cursor: Option<String>, limit: Option<i64>, outlet: Option<String>, article_id: Option<String>, kind: Option<String>, severity: Option<i64> }
 
 async fn feed(Path(feed): Path<String>, Query(q): Query<FeedQ>) -> Json<serde_json::Value> {
     let after = q.after.unwrap_or(0);
     let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
-    let mut url = format!("{}/feed/{}?after={}", idx, feed, after);
+    let mut url = match q.cursor.as_ref() {
+        Some(c) => format!("{}/feed/{}?cursor={}", idx, feed, urlencoding::encode(c)),
+        None => format!("{}/feed/{}?after={}", idx, feed, after),
+    };
+    if let Some(l) = q.limit { url.push_str(&format!("&limit={}", l)); }
     if let Some(o) = q.outlet.as_ref() { url.push_str(&format!("&outlet={}", urlencoding::encode(o))); }
     if let Some(a) = q.article_id.as_ref() { url.push_str(&format!("&article_id={}", urlencoding::encode(a))); }
     if let Some(k) = q.kind.as_ref() { url.push_str(&format!("&kind={}", urlencoding::encode(k))); }
@@ -475,9 +579,29 @@ async fn feed(Path(feed): Path<String>, Query(q): Query<FeedQ>) -> Json<serde_js
             return Json(j);
         }
     }
-    Json(serde_json::json!({"ok": true, "feed": feed, "after": after, "items": []}))
+    Json(serde_json::json!({"ok": false, "feed": feed, "error": "indexer unavailable", "items": []}))
 }
 
-    let after = q.after.unwrap_or(0);
-    Json(serde_json::json!({"ok": true, "feed": feed, "after": after, "items": []}))
+// Passes the indexer's SSE stream through unchanged, including Last-Event-ID for resuming.
+async fn feed_stream(Path(feed): Path<String>, axum::extract::RawQuery(raw): axum::extract::RawQuery, headers: axum::http::HeaderMap) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    let idx = std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into());
+    let url = match raw {
+        Some(qs) if !qs.is_empty() => format!("{}/feed/{}/stream?{}", idx, urlencoding::encode(&feed), qs),
+        _ => format!("{}/feed/{}/stream", idx, urlencoding::encode(&feed)),
+    };
+    let mut req = reqwest::Client::new().get(url).header("accept", "text/event-stream");
+    if let Some(id) = headers.get("last-event-id").and_then(|v| v.to_str().ok()) {
+        req = req.header("last-event-id", id);
+    }
+    match req.send().await {
+        Ok(r) => {
+            let ct = r.headers().get("content-type").and_then(|v| v.to_str().ok()).unwrap_or("text/event-stream").to_string();
+            (
+                [(axum::http::header::CONTENT_TYPE, ct), (axum::http::header::CACHE_CONTROL, "no-cache".to_string())],
+                axum::body::Body::from_stream(r.bytes_stream()),
+            ).into_response()
+        }
+        Err(_) => Json(serde_json::json!({"ok": false, "feed": feed, "error": "indexer unavailable"})).into_response(),
+    }
 }
@@ -0,0 +1,131 @@
+// Wallet role resolution for /roles/of.
+//
+// Combines the indexer's raw role facts (OutletRegistry grants per outlet, BondVault bonds,
+// CouncilRegistry membership) into a global role list and per-outlet role lists. Results are cached
+// per wallet; a background task polls the indexer's /roles/changes and evicts wallets whose role
+// events were indexed since the last poll, or the whole cache when the indexer reports a rollback. The TTL only bounds staleness from time-based changes
+// (council terms ending) and from a stalled invalidation loop.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+#[derive(Serialize, Clone, Default)]
+pub struct RoleSet {
+    pub wallet: String,
+    // protocol-wide roles: COUNCIL, COUNCIL_BOND, OUTLET_BOND
+    pub global: Vec<String>,
+    // outlet id -> OUTLET_MANAGER / OUTLET_EDITOR / ...
+    pub outlets: BTreeMap<String, Vec<String>>,
+}
+
+impl RoleSet {
+    // Global roles plus every outlet role, deduplicated; what callers that do not care about scope use.
+    pub fn all(&self) -> Vec<String> {
+        let mut v: Vec<String> = self.global.iter().chain(self.outlets.values().flatten()).cloned().collect();
+        v.sort();
+        v.dedup();
+        v
+    }
+}
+
+#[derive(Clone)]
+pub struct RoleStore {
+    cache: Arc<RwLock<HashMap<String, (Instant, RoleSet)>>>,
+    // last /roles/changes cursor (block number)
+    cursor: Arc<AtomicI64>,
+    // last /roles/changes rollback generation; -1 until the first poll
+    generation: Arc<AtomicI64>,
+    ttl: Duration,
+}
+
+fn indexer_api() -> String {
+    std::env::var("PRESS_INDEXER_API").unwrap_or_else(|_| "http://press-indexer:8786".into())
+}
+
+fn now_ts() -> i64 { chrono::Utc::now().timestamp() }
+
+impl RoleStore {
+    pub fn new() -> Self {
+        let ttl = std::env::var("ROLES_CACHE_TTL_SECS").ok().and_then(|s| s.parse::<u64>().ok()).unwrap_or(300);
+        RoleStore { cache: Arc::new(RwLock::new(HashMap::new())), cursor: Arc::new(AtomicI64::new(0)), generation: Arc::new(AtomicI64::new(-1)), ttl: Duration::from_secs(ttl) }
+    }
+
+    pub async fn resolve(&self, wallet: &str) -> Result<RoleSet, String> {
+        let wallet = wallet.trim().to_lowercase();
+        if let Some((at, set)) = self.cache.read().await.get(&wallet) {
+            if at.elapsed() < self.ttl { return Ok(set.clone()); }
+        }
+        let set = fetch(&wallet).await?;
+        self.cache.write().await.insert(wallet, (Instant::now(), set.clone()));
+        Ok(set)
+    }
+
+    // Flat role list (global and outlet-scoped) for one wallet.
+    pub async fn roles_of(&self, wallet: &str) -> Result<Vec<String>, String> {
+        Ok(self.resolve(wallet).await?.all())
+    }
+
+    async fn invalidate_changed(&self) -> Result<(), String> {
+        let after = self.cursor.load(Ordering::Relaxed);
+        let j = reqwest::Client::new().get(format!("{}/roles/changes?after={}", indexer_api(), after))
+            .send().await.map_err(|e| e.to_string())?
+            .json::<serde_json::Value>().await.map_err(|e| e.to_string())?;
+        let cursor = j.get("cursor").and_then(|v| v.as_i64()).unwrap_or(0);
+        let generation = j.get("generation").and_then(|v| v.as_i64()).unwrap_or(0);
+        let truncated = j.get("truncated").and_then(|v| v.as_bool()).unwrap_or(false);
+        let last_generation = self.generation.swap(generation, Ordering::Relaxed);
+        let rolled_back = cursor < after || (last_generation >= 0 && generation != last_generation);
+        let mut cache = self.cache.write().await;
+        if rolled_back || truncated {
+            // rows were rolled back (reorg) or too much changed to list: start over
+            cache.clear();
+        } else {
+            for w in j.get("wallets").and_then(|v| v.as_array()).into_iter().flatten() {
+                if let Some(w) = w.as_str() { cache.remove(&w.to_lowercase()); }
+            }
+        }
+        self.cursor.store(cursor, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+async fn fetch(wallet: &str) -> Result<RoleSet, String> {
+    let url = format!("{}/roles/wallet/{}", indexer_api(), urlencoding::encode(wallet));
+    let j = reqwest::Client::new().get(url).send().await.map_err(|e| e.to_string())?
+        .json::<serde_json::Value>().await.map_err(|e| e.to_string())?;
+    if j.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+        return Err(j.get("error").and_then(|e| e.as_str()).unwrap_or("indexer error").to_string());
+    }
+
+    let mut global = vec![];
+    if let Some(c) = j.get("council").filter(|c| !c.is_null()) {
+        let active = c.get("active").and_then(|v| v.as_bool()).unwrap_or(false);
+        let term_end = c.get("term_end").and_then(|v| v.as_i64()).unwrap_or(0);
+        if active && (term_end == 0 || term_end >= now_ts()) { global.push("COUNCIL".to_string()); }
+    }
+    for (role, amount) in j.get("bonds").and_then(|b| b.as_object()).into_iter().flatten() {
+        let bonded = amount.as_str().map(|a| a.trim_start_matches('0')).map(|a| !a.is_empty()).unwrap_or(false);
+        if bonded && (role == "COUNCIL_BOND" || role == "OUTLET_BOND") { global.push(role.clone()); }
+    }
+
+    let mut outlets = BTreeMap::new();
+    for (outlet, roles) in j.get("outlets").and_then(|o| o.as_object()).into_iter().flatten() {
+        let roles: Vec<String> = roles.as_array().into_iter().flatten().filter_map(|r| r.as_str().map(|s| s.to_string())).collect();
+        if !roles.is_empty() { outlets.insert(outlet.clone(), roles); }
+    }
+    Ok(RoleSet { wallet: wallet.to_string(), global, outlets })
+}
+
+pub async fn invalidate_loop(store: RoleStore) {
+    let every = std::env::var("ROLES_INVALIDATE_POLL_MS").ok().and_then(|s| s.parse::<u64>().ok()).unwrap_or(5000);
+    loop {
+        if let Err(e) = store.invalidate_changed().await {
+            eprintln!("roles: invalidation poll failed: {}", e);
+        }
+        tokio::time::sleep(Duration::from_millis(every)).await;
+    }
+}
@@ -5,6 +5,7 @@ use std::{env, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
+use press_metrics::RouterExt;
 
 #[derive(Clone)]
 struct AppState {
@@ -42,6 +43,7 @@ impl Default for StatusConfig {
                                 StatusTarget{ id:"gateway".into(), name:"Gateway".into(), url:"http://deployer-gateway:8085/health".into(), kind:"http".into(), critical:true, json_field: None, json_bool_required: None , json_num_field: None, json_num_max: None},
                 StatusTarget{ id:"rpc".into(), name:"RPC".into(), url:"http://press-rpc:8545".into(), kind:"http".into(), critical:true, json_field: None, json_bool_required: None , json_num_field: None, json_num_max: None},
                 StatusTarget{ id:"indexer".into(), name:"Indexer".into(), url:"http://press-indexer:8786/health".into(), kind:"http".into(), critical:true, json_field: None, json_bool_required: None , json_num_field: None, json_num_max: None},
+                StatusTarget{ id:"indexer_streams".into(), name:"Indexer — Streams".into(), url:"http://press-indexer:8786/streams".into(), kind:"json".into(), critical:false, json_field: Some("ok".into()), json_bool_required: Some(true), json_num_field: Some("stalled".into()), json_num_max: Some(0) },
                 StatusTarget{ id:"query".into(), name:"Query API".into(), url:"http://query-api:8787/health".into(), kind:"http".into(), critical:true, json_field: None, json_bool_required: None , json_num_field: None, json_num_max: None},
                 StatusTarget{ id:"bots_discord".into(), name:"Bots — Discord".into(), url:"http://press-bots:8790/health".into(), kind:"json".into(), critical:false, json_field: Some("discord_connected".into()), json_bool_required: Some(true) , json_num_field: None, json_num_max: None},
                 StatusTarget{ id:"bots_telegram".into(), name:"Bots — Telegram".into(), url:"http://press-bots:8790/health".into(), kind:"json".into(), critical:false, json_field: Some("telegram_connected".into()), json_bool_required: Some(true) , json_num_field: None, json_num_max: None},
@@ -100,6 +102,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/health", get(|| async { Json(serde_json::json!({"ok":true,"service":"press_status_service"})) }))
         .route("/api/status/config", get(get_config).post(set_config))
         .route("/api/status/summary", get(get_summary))
+        .with_metrics("status_service")
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state);